@@ -1,7 +1,17 @@
-use specs::{storage::BTreeStorage, Component, NullStorage, VecStorage};
+use std::collections::HashSet;
+use std::ops::Deref;
+
+use rand::Rng;
+use specs::{
+    join::Join,
+    storage::{BTreeStorage, MaskedStorage},
+    Component, Entities, Entity, NullStorage, Storage, VecStorage, WriteStorage,
+};
 
 // TODO: review all tcod usage here
-use tcod::Color;
+use tcod::{colors, Color};
+
+use crate::map::AreaShape;
 
 #[derive(Debug)]
 pub struct Position {
@@ -29,3 +39,1462 @@ pub struct Player;
 impl Component for Player {
     type Storage = NullStorage<Self>;
 }
+
+/// Thinks for itself, see the `MonsterAi` system
+#[derive(Debug, Default)]
+pub struct Ai {
+    pub state: AiState,
+}
+
+impl Component for Ai {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AiState {
+    // standing around, every now and then it picks somewhere to go
+    #[default]
+    Idle,
+    Wander {
+        goal: (i32, i32),
+    },
+    // after the player, who's in sight
+    Hunt {
+        last_seen: (i32, i32),
+    },
+    // lost sight of the player, checking where they were last seen
+    Search {
+        last_seen: (i32, i32),
+        turns_left: i32,
+    },
+    // too hurt to fight, getting away from the player
+    Flee,
+}
+
+impl AiState {
+    pub fn name(self) -> &'static str {
+        match self {
+            AiState::Idle => "idle",
+            AiState::Wander { .. } => "wander",
+            AiState::Hunt { .. } => "hunt",
+            AiState::Search { .. } => "search",
+            AiState::Flee => "flee",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Dead;
+
+impl Component for Dead {
+    type Storage = NullStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct Name(pub String);
+
+impl Name {
+    pub fn of<D>(names: &Storage<Name, D>, entity: Entity) -> String
+    where
+        D: Deref<Target = MaskedStorage<Name>>,
+    {
+        names
+            .get(entity)
+            .map_or_else(|| "something".to_string(), |n| n.0.clone())
+    }
+}
+
+impl Component for Name {
+    type Storage = VecStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct HitPoints {
+    pub max: i32,
+    pub current: i32,
+}
+
+impl HitPoints {
+    pub fn new(max: i32) -> Self {
+        HitPoints { max, current: max }
+    }
+
+    pub fn alive(&self) -> bool {
+        self.current > 0
+    }
+}
+
+impl Component for HitPoints {
+    type Storage = VecStorage<Self>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageType {
+    Kinetic,
+    Thermal,
+    Electric,
+    Explosive,
+    Corrosive,
+}
+
+impl DamageType {
+    pub fn name(self) -> &'static str {
+        use DamageType::*;
+
+        match self {
+            Kinetic => "kinetic",
+            Thermal => "thermal",
+            Electric => "electric",
+            Explosive => "explosive",
+            Corrosive => "corrosive",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Melee {
+    pub attack: i32,
+    pub defense: i32,
+    pub damage_type: DamageType,
+    pub on_hit: Option<StatusEffect>,
+}
+
+impl Component for Melee {
+    type Storage = VecStorage<Self>;
+}
+
+/// Damage multipliers per damage type, 1.0 means the type is taken as is
+#[derive(Debug)]
+pub struct Resistances {
+    pub kinetic: f32,
+    pub thermal: f32,
+    pub electric: f32,
+    pub explosive: f32,
+    pub corrosive: f32,
+}
+
+impl Default for Resistances {
+    fn default() -> Self {
+        Resistances {
+            kinetic: 1.0,
+            thermal: 1.0,
+            electric: 1.0,
+            explosive: 1.0,
+            corrosive: 1.0,
+        }
+    }
+}
+
+impl Resistances {
+    pub fn multiplier(&self, damage_type: DamageType) -> f32 {
+        use DamageType::*;
+
+        match damage_type {
+            Kinetic => self.kinetic,
+            Thermal => self.thermal,
+            Electric => self.electric,
+            Explosive => self.explosive,
+            Corrosive => self.corrosive,
+        }
+    }
+}
+
+impl Component for Resistances {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// What a mech's plating is made of, each holds up better against some
+/// damage types than others
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArmorType {
+    Composite,
+    Reflective,
+    Ablative,
+}
+
+impl ArmorType {
+    pub fn name(self) -> &'static str {
+        use ArmorType::*;
+
+        match self {
+            Composite => "composite",
+            Reflective => "reflective",
+            Ablative => "ablative",
+        }
+    }
+
+    /// Armor points stripped for every point of damage the plating stops
+    pub fn wear(self, damage_type: DamageType) -> i32 {
+        use ArmorType::*;
+        use DamageType::*;
+
+        match (self, damage_type) {
+            (Composite, Kinetic) | (Composite, Explosive) => 1,
+            (Reflective, Thermal) | (Reflective, Electric) => 1,
+            (Ablative, Thermal) | (Ablative, Explosive) | (Ablative, Corrosive) => 1,
+            // acid eats through anything not made to shrug it off
+            (_, Corrosive) => 3,
+            _ => 2,
+        }
+    }
+}
+
+/// Walking somewhere one step a turn, until it gets there or something comes up
+#[derive(Debug)]
+pub struct Travel {
+    // the steps left, the next one last
+    pub steps: Vec<(i32, i32)>,
+}
+
+impl Component for Travel {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Heading for whatever hasn't been explored yet, a step a turn
+#[derive(Debug, Default)]
+pub struct AutoExplore;
+
+impl Component for AutoExplore {
+    type Storage = NullStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct WantsToMelee {
+    pub target: Entity,
+}
+
+impl Component for WantsToMelee {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// How an attack came about, kept around for the combat log
+#[derive(Clone, Debug, Default)]
+pub struct Attack {
+    pub weapon: String,
+    // d100 roll and the chance it had to beat, attacks that can't miss have none
+    pub roll: Option<(i32, i32)>,
+    pub modifiers: Vec<(&'static str, i32)>,
+}
+
+impl Attack {
+    pub fn new(weapon: &str) -> Self {
+        Attack {
+            weapon: weapon.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn rolled(mut self, roll: i32, chance: i32) -> Self {
+        self.roll = Some((roll, chance));
+        self
+    }
+
+    pub fn modifier(mut self, name: &'static str, value: i32) -> Self {
+        self.modifiers.push((name, value));
+        self
+    }
+
+    pub fn hits(&self) -> bool {
+        self.roll.is_none_or(|(roll, chance)| roll < chance)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Hit {
+    pub source: Entity,
+    pub amount: i32,
+    pub damage_type: DamageType,
+    // lingering effect inflicted when the hit does damage
+    pub effect: Option<StatusEffect>,
+    pub attack: Attack,
+}
+
+/// Hits landed on an entity this turn, waiting to be resolved against its defenses
+#[derive(Debug, Default)]
+pub struct SufferDamage {
+    pub hits: Vec<Hit>,
+}
+
+impl SufferDamage {
+    pub fn add_hit(store: &mut WriteStorage<SufferDamage>, victim: Entity, hit: Hit) {
+        if let Some(suffering) = store.get_mut(victim) {
+            suffering.hits.push(hit);
+        } else {
+            // only fails for dead entities, which can't be hit anyway
+            let _ = store.insert(victim, SufferDamage { hits: vec![hit] });
+        }
+    }
+}
+
+impl Component for SufferDamage {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct WantsToMove {
+    pub dx: i32,
+    pub dy: i32,
+    pub sprint: bool,
+}
+
+impl Component for WantsToMove {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartKind {
+    Torso,
+    LeftArm,
+    RightArm,
+    Legs,
+    Sensors,
+}
+
+impl PartKind {
+    pub fn name(self) -> &'static str {
+        use PartKind::*;
+
+        match self {
+            Torso => "torso",
+            LeftArm => "left arm",
+            RightArm => "right arm",
+            Legs => "legs",
+            Sensors => "sensors",
+        }
+    }
+
+    pub fn abbr(self) -> &'static str {
+        use PartKind::*;
+
+        match self {
+            Torso => "TO",
+            LeftArm => "LA",
+            RightArm => "RA",
+            Legs => "LG",
+            Sensors => "SE",
+        }
+    }
+
+    /// What can be pulled out of this part on a wreck, as long as it's intact
+    pub fn salvage(self) -> Option<&'static str> {
+        use PartKind::*;
+
+        match self {
+            Torso => None,
+            LeftArm | RightArm => Some("servo"),
+            Legs => Some("actuator"),
+            Sensors => Some("optics"),
+        }
+    }
+
+    // relative chance of a hit landing on this part
+    fn hit_weight(self) -> i32 {
+        use PartKind::*;
+
+        match self {
+            Torso => 35,
+            LeftArm | RightArm => 15,
+            Legs => 25,
+            Sensors => 10,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MechPart {
+    pub kind: PartKind,
+    pub armor: i32,
+    pub max_armor: i32,
+    pub armor_type: ArmorType,
+    pub structure: i32,
+    pub max_structure: i32,
+}
+
+impl MechPart {
+    pub fn new(kind: PartKind, armor: i32, structure: i32) -> Self {
+        MechPart {
+            kind,
+            armor,
+            max_armor: armor,
+            armor_type: ArmorType::Composite,
+            structure,
+            max_structure: structure,
+        }
+    }
+
+    pub fn destroyed(&self) -> bool {
+        self.structure <= 0
+    }
+
+    /// Run damage through the plating, returning what is left for the structure
+    fn absorb(&mut self, damage: i32, damage_type: DamageType) -> i32 {
+        let wear = self.armor_type.wear(damage_type);
+        let stopped = damage.min(self.armor / wear);
+        self.armor -= stopped * wear;
+
+        // whatever gets through tears off the plating that was left
+        if stopped < damage {
+            self.armor = 0;
+        }
+
+        damage - stopped
+    }
+}
+
+/// Outcome of a hit resolved against a mech body
+#[derive(Debug)]
+pub struct PartHit {
+    pub part: PartKind,
+    pub damage: i32,
+    pub absorbed: i32,
+    // the plating just gave out
+    pub breached: bool,
+    pub destroyed: bool,
+}
+
+/// A mech is made of parts, each soaking up the hits that land on it. The mech
+/// goes down with its torso, other destroyed parts disable what they carry.
+#[derive(Debug)]
+pub struct MechBody {
+    pub parts: Vec<MechPart>,
+    // crippled legs only carry the mech every other turn
+    pub staggered: bool,
+}
+
+impl MechBody {
+    pub fn new(parts: Vec<MechPart>) -> Self {
+        MechBody {
+            parts,
+            staggered: false,
+        }
+    }
+
+    /// Use the same plating all over the body
+    pub fn plated(mut self, armor_type: ArmorType) -> Self {
+        for part in &mut self.parts {
+            part.armor_type = armor_type;
+        }
+        self
+    }
+
+    pub fn part(&self, kind: PartKind) -> Option<&MechPart> {
+        self.parts.iter().find(|p| p.kind == kind)
+    }
+
+    pub fn operational(&self, kind: PartKind) -> bool {
+        self.part(kind).is_some_and(|p| !p.destroyed())
+    }
+
+    pub fn torso_structure(&self) -> i32 {
+        self.part(PartKind::Torso).map_or(0, |p| p.structure.max(0))
+    }
+
+    pub fn can_punch(&self) -> bool {
+        self.operational(PartKind::LeftArm) || self.operational(PartKind::RightArm)
+    }
+
+    pub fn sight_radius(&self, base: i32) -> i32 {
+        if self.operational(PartKind::Sensors) {
+            base
+        } else {
+            base / 2
+        }
+    }
+
+    pub fn needs_repair(&self) -> bool {
+        self.parts
+            .iter()
+            .any(|p| !p.destroyed() && p.armor < p.max_armor)
+    }
+
+    /// Mend the working part that lost the most structure, returning the part
+    /// and how much structure it got back. Destroyed parts are beyond fixing.
+    pub fn heal(&mut self, amount: i32) -> Option<(PartKind, i32)> {
+        let part = self
+            .parts
+            .iter_mut()
+            .filter(|p| !p.destroyed() && p.structure < p.max_structure)
+            .max_by_key(|p| p.max_structure - p.structure)?;
+
+        let restored = amount.min(part.max_structure - part.structure);
+        part.structure += restored;
+        Some((part.kind, restored))
+    }
+
+    /// Patch up the working part that lost the most armor, returning the part
+    /// and how much armor it got back.
+    pub fn repair(&mut self, amount: i32) -> Option<(PartKind, i32)> {
+        let part = self
+            .parts
+            .iter_mut()
+            .filter(|p| !p.destroyed() && p.armor < p.max_armor)
+            .max_by_key(|p| p.max_armor - p.armor)?;
+
+        let restored = amount.min(part.max_armor - part.armor);
+        part.armor += restored;
+        Some((part.kind, restored))
+    }
+
+    /// Damage that comes from within skips the armor and goes to the torso
+    pub fn internal_damage(&mut self, amount: i32) {
+        if let Some(torso) = self.parts.iter_mut().find(|p| p.kind == PartKind::Torso) {
+            torso.structure -= amount;
+        }
+    }
+
+    /// Roll a hit location and apply damage to it, hits on destroyed parts
+    /// go through to the torso. Armor soaks up what it can before the
+    /// structure takes the rest.
+    pub fn take_hit(&mut self, damage: i32, damage_type: DamageType) -> PartHit {
+        let total: i32 = self.parts.iter().map(|p| p.kind.hit_weight()).sum();
+        let mut roll = rand::thread_rng().gen_range(0, total);
+
+        let mut kind = PartKind::Torso;
+        for part in &self.parts {
+            roll -= part.kind.hit_weight();
+            if roll < 0 {
+                kind = part.kind;
+                break;
+            }
+        }
+
+        if !self.operational(kind) {
+            kind = PartKind::Torso;
+        }
+
+        let part = self
+            .parts
+            .iter_mut()
+            .find(|p| p.kind == kind)
+            .expect("every mech has a torso");
+
+        let was_destroyed = part.destroyed();
+        let was_plated = part.armor > 0;
+        let through = part.absorb(damage.max(0), damage_type);
+        part.structure -= through;
+
+        PartHit {
+            part: kind,
+            damage: through,
+            absorbed: damage.max(0) - through,
+            breached: was_plated && part.armor == 0,
+            destroyed: !was_destroyed && part.destroyed(),
+        }
+    }
+}
+
+impl Component for MechBody {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HeatLevel {
+    Normal,
+    Hot,
+    Critical,
+    Overheated,
+}
+
+#[derive(Debug)]
+pub struct Heat {
+    pub current: i32,
+    pub max: i32,
+    // heat sinks get rid of this much heat every turn
+    pub dissipation: i32,
+    // turns left before a shut down mech comes back online
+    pub shutdown: i32,
+}
+
+impl Heat {
+    pub fn new(max: i32, dissipation: i32) -> Self {
+        Heat {
+            current: 0,
+            max,
+            dissipation,
+            shutdown: 0,
+        }
+    }
+
+    /// Heat can only build up so far past the limit, just enough that the
+    /// heat sinks can't bring it back under before the mech overheats
+    pub fn add(&mut self, amount: i32) {
+        self.current = (self.current + amount).min(self.max + self.dissipation);
+    }
+
+    pub fn level(&self) -> HeatLevel {
+        let ratio = self.current as f32 / self.max as f32;
+        if ratio >= 1.0 {
+            HeatLevel::Overheated
+        } else if ratio >= 0.8 {
+            HeatLevel::Critical
+        } else if ratio >= 0.5 {
+            HeatLevel::Hot
+        } else {
+            HeatLevel::Normal
+        }
+    }
+
+    /// Chance out of 100 to miss because of the heat
+    pub fn accuracy_penalty(&self) -> i32 {
+        match self.level() {
+            HeatLevel::Normal => 0,
+            HeatLevel::Hot => 10,
+            HeatLevel::Critical => 25,
+            HeatLevel::Overheated => 40,
+        }
+    }
+
+    pub fn shut_down(&self) -> bool {
+        self.shutdown > 0
+    }
+}
+
+impl Component for Heat {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct Viewshed {
+    pub visible: HashSet<(i32, i32)>,
+    pub range: i32,
+}
+
+impl Viewshed {
+    pub fn new(range: i32) -> Self {
+        Viewshed {
+            visible: HashSet::new(),
+            range,
+        }
+    }
+
+    pub fn can_see(&self, x: i32, y: i32) -> bool {
+        self.visible.contains(&(x, y))
+    }
+}
+
+impl Component for Viewshed {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct RangedWeapon {
+    pub name: String,
+    pub range: i32,
+    pub damage: i32,
+    pub damage_type: DamageType,
+    // base chance out of 100 to hit a target right next to the shooter
+    pub accuracy: i32,
+    pub ammo_kind: AmmoKind,
+    pub magazine: i32,
+    pub loaded: i32,
+    // turns it takes to put a fresh magazine in
+    pub reload_turns: i32,
+    pub heat: i32,
+    // the part carrying the weapon, it can't fire once that part is destroyed
+    pub mount: PartKind,
+    pub on_hit: Option<StatusEffect>,
+    // weapons with a warhead blow up an area instead of hitting a single target
+    pub warhead: Option<Warhead>,
+    // tiles a hit pushes the target back
+    pub knockback: i32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Warhead {
+    pub shape: AreaShape,
+    // missiles per salvo, each landing somewhere around the target
+    pub count: i32,
+}
+
+impl RangedWeapon {
+    pub fn hit_chance(&self, distance: f32, penalty: i32) -> i32 {
+        let chance = self.accuracy - (distance * 3.0) as i32 - penalty;
+        chance.clamp(5, 95)
+    }
+}
+
+impl Component for RangedWeapon {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmmoKind {
+    Shells,
+    Fuel,
+    Missiles,
+}
+
+impl AmmoKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            AmmoKind::Shells => "shells",
+            AmmoKind::Fuel => "fuel canisters",
+            AmmoKind::Missiles => "missiles",
+        }
+    }
+}
+
+/// Loose rounds, used to refill the magazine of a weapon taking the same kind.
+/// The rounds are the quantity of its stack.
+#[derive(Debug)]
+pub struct Ammo {
+    pub kind: AmmoKind,
+}
+
+impl Ammo {
+    /// Rounds of `kind` carried by `owner`
+    pub fn carried<D, S, B>(
+        ammo: &Storage<Ammo, D>,
+        stacks: &Storage<Stackable, S>,
+        backpack: &Storage<InBackpack, B>,
+        owner: Entity,
+        kind: AmmoKind,
+    ) -> i32
+    where
+        D: Deref<Target = MaskedStorage<Ammo>>,
+        S: Deref<Target = MaskedStorage<Stackable>>,
+        B: Deref<Target = MaskedStorage<InBackpack>>,
+    {
+        (ammo, stacks, backpack)
+            .join()
+            .filter(|(a, _, b)| b.owner == owner && a.kind == kind)
+            .map(|(_, s, _)| s.quantity)
+            .sum()
+    }
+}
+
+impl Component for Ammo {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Anything that can be picked up and carried around
+#[derive(Debug)]
+pub struct Item {
+    pub weight: i32,
+    // what a vendor asks for one, they pay less
+    pub value: i32,
+}
+
+impl Component for Item {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Identical items carried as one, up to `limit` of them. Items are identical
+/// when they have the same name.
+#[derive(Debug)]
+pub struct Stackable {
+    pub quantity: i32,
+    pub limit: i32,
+}
+
+impl Stackable {
+    pub fn new(limit: i32) -> Self {
+        Stackable { quantity: 1, limit }
+    }
+
+    /// Takes `amount` off the stack, true once there's nothing left of it
+    pub fn take(&mut self, amount: i32) -> bool {
+        self.quantity -= amount;
+        self.quantity <= 0
+    }
+
+    /// Stacks carried by `owner` with room left for more of `item`
+    pub fn matching<S, B, N>(
+        stacks: &Storage<Stackable, S>,
+        backpack: &Storage<InBackpack, B>,
+        names: &Storage<Name, N>,
+        entities: &Entities,
+        owner: Entity,
+        item: Entity,
+    ) -> Vec<Entity>
+    where
+        S: Deref<Target = MaskedStorage<Stackable>>,
+        B: Deref<Target = MaskedStorage<InBackpack>>,
+        N: Deref<Target = MaskedStorage<Name>>,
+    {
+        if !stacks.contains(item) {
+            return vec![];
+        }
+
+        let name = names.get(item).map(|n| &n.0);
+        (entities, stacks, backpack, names)
+            .join()
+            .filter(|(e, s, b, n)| {
+                *e != item && b.owner == owner && s.quantity < s.limit && Some(&n.0) == name
+            })
+            .map(|(e, _, _, _)| e)
+            .collect()
+    }
+}
+
+impl Component for Stackable {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct InBackpack {
+    pub owner: Entity,
+}
+
+impl InBackpack {
+    /// Number of items carried by `owner`
+    pub fn count<D>(backpack: &Storage<InBackpack, D>, owner: Entity) -> usize
+    where
+        D: Deref<Target = MaskedStorage<InBackpack>>,
+    {
+        backpack.join().filter(|b| b.owner == owner).count()
+    }
+}
+
+impl Component for InBackpack {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct WantsToPickUp {
+    pub item: Entity,
+}
+
+impl Component for WantsToPickUp {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct WantsToDrop {
+    pub item: Entity,
+    // how many off the stack, anything less than all of it splits the stack
+    pub quantity: i32,
+}
+
+impl Component for WantsToDrop {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct WantsToUseItem {
+    pub item: Entity,
+    // where it's thrown, for items that need a target
+    pub target: Option<(i32, i32)>,
+}
+
+impl Component for WantsToUseItem {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Used up when used. What using it does comes from the effect components
+/// below, an item can carry any mix of them.
+#[derive(Debug, Default)]
+pub struct Consumable;
+
+impl Component for Consumable {
+    type Storage = NullStorage<Self>;
+}
+
+/// Goes by its appearance until its kind is identified, see `Identification`
+#[derive(Debug)]
+pub struct Unidentified {
+    pub real: String,
+}
+
+impl Component for Unidentified {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Identifies everything unknown its user carries
+#[derive(Debug, Default)]
+pub struct Identifies;
+
+impl Component for Identifies {
+    type Storage = NullStorage<Self>;
+}
+
+/// Permanently improves the user's ranged weapon
+#[derive(Debug)]
+pub struct UpgradesWeapon {
+    pub damage: i32,
+    pub accuracy: i32,
+}
+
+impl Component for UpgradesWeapon {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Restores hit points, or the structure of a mech's parts
+#[derive(Debug)]
+pub struct ProvidesHealing {
+    pub amount: i32,
+}
+
+impl Component for ProvidesHealing {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Spare plating to patch up damaged armor with
+#[derive(Debug)]
+pub struct ProvidesRepair {
+    pub armor: i32,
+}
+
+impl Component for ProvidesRepair {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Dumps heat out of the mech
+#[derive(Debug)]
+pub struct ProvidesCooling {
+    pub heat: i32,
+}
+
+impl Component for ProvidesCooling {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Marks the whole map as explored
+#[derive(Debug, Default)]
+pub struct RevealsMap;
+
+impl Component for RevealsMap {
+    type Storage = NullStorage<Self>;
+}
+
+/// Throws the user somewhere random on the map
+#[derive(Debug, Default)]
+pub struct Teleports;
+
+impl Component for Teleports {
+    type Storage = NullStorage<Self>;
+}
+
+/// Thrown at a tile up to `range` away instead of used on oneself
+#[derive(Debug)]
+pub struct Targeted {
+    pub range: i32,
+}
+
+impl Component for Targeted {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Affects everything around where it lands, not just that one tile
+#[derive(Debug)]
+pub struct AreaOfEffect {
+    pub shape: AreaShape,
+}
+
+impl Component for AreaOfEffect {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct InflictsDamage {
+    pub damage: i32,
+    pub damage_type: DamageType,
+}
+
+impl Component for InflictsDamage {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Puts a status effect on whatever it hits, armor or not
+#[derive(Debug)]
+pub struct InflictsEffect {
+    pub effect: StatusEffect,
+}
+
+impl Component for InflictsEffect {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Lights up where it lands for a few turns
+#[derive(Debug)]
+pub struct Illuminates {
+    pub turns: i32,
+}
+
+impl Component for Illuminates {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// A burning flare, the player sees its tiles no matter where they stand
+#[derive(Debug)]
+pub struct Flare {
+    pub tiles: Vec<(i32, i32)>,
+    pub turns_left: i32,
+}
+
+impl Component for Flare {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Where a mech can bolt on weapons and modules
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hardpoint {
+    LeftArm,
+    RightArm,
+    Shoulder,
+    Torso,
+    Legs,
+}
+
+impl Hardpoint {
+    pub fn name(self) -> &'static str {
+        match self {
+            Hardpoint::LeftArm => "left arm",
+            Hardpoint::RightArm => "right arm",
+            Hardpoint::Shoulder => "shoulder",
+            Hardpoint::Torso => "torso",
+            Hardpoint::Legs => "legs",
+        }
+    }
+
+    /// The part the hardpoint is bolted onto, whatever is mounted goes down with it
+    pub fn part(self) -> PartKind {
+        match self {
+            Hardpoint::LeftArm => PartKind::LeftArm,
+            Hardpoint::RightArm => PartKind::RightArm,
+            Hardpoint::Shoulder | Hardpoint::Torso => PartKind::Torso,
+            Hardpoint::Legs => PartKind::Legs,
+        }
+    }
+}
+
+/// A hardpoint takes anything up to its own size
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MountSize {
+    Light,
+    Medium,
+    Heavy,
+}
+
+impl MountSize {
+    pub fn name(self) -> &'static str {
+        match self {
+            MountSize::Light => "light",
+            MountSize::Medium => "medium",
+            MountSize::Heavy => "heavy",
+        }
+    }
+}
+
+/// The hardpoints of a mech, and how big each one is
+#[derive(Debug)]
+pub struct Hardpoints {
+    pub slots: Vec<(Hardpoint, MountSize)>,
+}
+
+impl Hardpoints {
+    /// Hardpoints able to take `item`, in the order they're tried
+    pub fn fitting<'a>(&'a self, item: &'a Equippable) -> impl Iterator<Item = Hardpoint> + 'a {
+        self.slots
+            .iter()
+            .filter(move |(slot, size)| item.mounts.contains(slot) && item.size <= *size)
+            .map(|(slot, _)| *slot)
+    }
+}
+
+impl Component for Hardpoints {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Can be mounted on any of `mounts`, as long as the hardpoint is big enough
+#[derive(Debug)]
+pub struct Equippable {
+    pub mounts: Vec<Hardpoint>,
+    pub size: MountSize,
+}
+
+impl Component for Equippable {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Mounted on one of `owner`'s hardpoints, out of the backpack
+#[derive(Debug)]
+pub struct Equipped {
+    pub owner: Entity,
+    pub slot: Hardpoint,
+}
+
+impl Equipped {
+    /// What `owner` has mounted on `slot`, if anything
+    pub fn on<D>(
+        equipped: &Storage<Equipped, D>,
+        entities: &Entities,
+        owner: Entity,
+        slot: Hardpoint,
+    ) -> Option<Entity>
+    where
+        D: Deref<Target = MaskedStorage<Equipped>>,
+    {
+        (entities, equipped)
+            .join()
+            .find(|(_, e)| e.owner == owner && e.slot == slot)
+            .map(|(item, _)| item)
+    }
+
+    /// A bonus summed up over everything `owner` has mounted on parts that
+    /// are still in one piece
+    pub fn bonus<B, D, S, M>(
+        equipped: &Storage<Equipped, D>,
+        bonuses: &Storage<B, S>,
+        bodies: &Storage<MechBody, M>,
+        owner: Entity,
+        value: impl Fn(&B) -> i32,
+    ) -> i32
+    where
+        B: Component,
+        D: Deref<Target = MaskedStorage<Equipped>>,
+        S: Deref<Target = MaskedStorage<B>>,
+        M: Deref<Target = MaskedStorage<MechBody>>,
+    {
+        let body = bodies.get(owner);
+        (equipped, bonuses)
+            .join()
+            .filter(|(e, _)| e.owner == owner)
+            .filter(|(e, _)| body.is_none_or(|b| b.operational(e.slot.part())))
+            .map(|(_, b)| value(b))
+            .sum()
+    }
+}
+
+impl Component for Equipped {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// A gun that bolts onto a hardpoint. Its `RangedWeapon` moves onto the mech
+/// while mounted, and comes back along with the item when taken off.
+#[derive(Debug, Default)]
+pub struct Armament;
+
+impl Component for Armament {
+    type Storage = NullStorage<Self>;
+}
+
+/// Extra melee damage while mounted
+#[derive(Debug)]
+pub struct MeleeBonus {
+    pub attack: i32,
+}
+
+impl Component for MeleeBonus {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Takes the edge off incoming hits while mounted
+#[derive(Debug)]
+pub struct DefenseBonus {
+    pub defense: i32,
+}
+
+impl Component for DefenseBonus {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Better odds for ranged shots while mounted
+#[derive(Debug)]
+pub struct AccuracyBonus {
+    pub accuracy: i32,
+}
+
+impl Component for AccuracyBonus {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Less heat from sprinting while mounted
+#[derive(Debug)]
+pub struct MobilityBonus {
+    pub sprint_heat: i32,
+}
+
+impl Component for MobilityBonus {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct WantsToEquip {
+    pub item: Entity,
+}
+
+impl Component for WantsToEquip {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct WantsToUnequip {
+    pub item: Entity,
+}
+
+impl Component for WantsToUnequip {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Credits to spend at vendors
+#[derive(Debug)]
+pub struct Wallet {
+    pub credits: i32,
+}
+
+impl Component for Wallet {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Sells what it carries and buys what the player carries, never fights
+#[derive(Debug, Default)]
+pub struct Vendor;
+
+impl Component for Vendor {
+    type Storage = NullStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct WantsToBuy {
+    pub vendor: Entity,
+    pub item: Entity,
+}
+
+impl Component for WantsToBuy {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct WantsToSell {
+    pub vendor: Entity,
+    pub item: Entity,
+}
+
+impl Component for WantsToSell {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Rolled `rolls` times on the named table in `LootTables` when it dies
+#[derive(Debug)]
+pub struct LootTable {
+    pub table: &'static str,
+    pub rolls: i32,
+}
+
+impl Component for LootTable {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// A wreck that hasn't been stripped for parts yet
+#[derive(Debug)]
+pub struct Salvageable {
+    pub scrap: i32,
+    pub parts: Vec<&'static str>,
+}
+
+impl Salvageable {
+    /// Scrap from the whole frame, and a part from everything still intact
+    pub fn from_body(body: &MechBody) -> Self {
+        let frame: i32 = body.parts.iter().map(|p| p.max_structure).sum();
+        Salvageable {
+            scrap: (frame / 10).max(1),
+            parts: body
+                .parts
+                .iter()
+                .filter(|p| !p.destroyed())
+                .filter_map(|p| p.kind.salvage())
+                .collect(),
+        }
+    }
+}
+
+impl Component for Salvageable {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct WantsToSalvage {
+    pub wreck: Entity,
+}
+
+impl Component for WantsToSalvage {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct WantsToCraft {
+    // index into the `Recipes`
+    pub recipe: usize,
+}
+
+impl Component for WantsToCraft {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct Reloading {
+    pub turns_left: i32,
+}
+
+impl Component for Reloading {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Debug)]
+pub struct WantsToShoot {
+    pub target: (i32, i32),
+}
+
+impl Component for WantsToShoot {
+    type Storage = BTreeStorage<Self>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EffectKind {
+    Stunned,
+    Burning,
+    Emp,
+    Corroding,
+}
+
+impl EffectKind {
+    pub fn name(self) -> &'static str {
+        use EffectKind::*;
+
+        match self {
+            Stunned => "stunned",
+            Burning => "burning",
+            Emp => "scrambled by EMP",
+            Corroding => "corroding",
+        }
+    }
+
+    pub fn icon(self) -> (char, Color) {
+        use EffectKind::*;
+
+        match self {
+            Stunned => ('*', colors::YELLOW),
+            Burning => ('^', colors::ORANGE),
+            Emp => ('~', colors::LIGHT_BLUE),
+            Corroding => ('&', colors::LIME),
+        }
+    }
+
+    // how a new instance combines with one already active
+    fn stacking(self) -> Stacking {
+        use EffectKind::*;
+
+        match self {
+            Stunned => Stacking::Refresh,
+            Emp => Stacking::Extend,
+            Burning | Corroding => Stacking::Intensify(3),
+        }
+    }
+}
+
+enum Stacking {
+    // keep the longer of the two durations
+    Refresh,
+    // durations add up
+    Extend,
+    // up to this many stacks, each stack refreshes the duration
+    Intensify(i32),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct StatusEffect {
+    pub kind: EffectKind,
+    pub turns: i32,
+    pub stacks: i32,
+}
+
+impl StatusEffect {
+    pub fn new(kind: EffectKind, turns: i32) -> Self {
+        StatusEffect {
+            kind,
+            turns,
+            stacks: 1,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct StatusEffects {
+    pub effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    pub fn apply(&mut self, effect: StatusEffect) {
+        let active = match self.effects.iter_mut().find(|e| e.kind == effect.kind) {
+            Some(active) => active,
+            None => {
+                self.effects.push(effect);
+                return;
+            }
+        };
+
+        match effect.kind.stacking() {
+            Stacking::Refresh => active.turns = active.turns.max(effect.turns),
+            Stacking::Extend => active.turns += effect.turns,
+            Stacking::Intensify(max) => {
+                active.stacks = (active.stacks + effect.stacks).min(max);
+                active.turns = active.turns.max(effect.turns);
+            }
+        }
+    }
+
+    pub fn has(&self, kind: EffectKind) -> bool {
+        self.effects.iter().any(|e| e.kind == kind)
+    }
+
+    pub fn inflict(store: &mut WriteStorage<StatusEffects>, victim: Entity, effect: StatusEffect) {
+        match store.get_mut(victim) {
+            Some(active) => active.apply(effect),
+            None => {
+                let _ = store.insert(
+                    victim,
+                    StatusEffects {
+                        effects: vec![effect],
+                    },
+                );
+            }
+        }
+    }
+}
+
+impl Component for StatusEffects {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// An explosion about to go off, resolved and removed by the `Explosions` system
+#[derive(Debug)]
+pub struct Blast {
+    pub center: (i32, i32),
+    pub toward: (i32, i32),
+    pub shape: AreaShape,
+    pub damage: i32,
+    pub damage_type: DamageType,
+    pub effect: Option<StatusEffect>,
+    pub knockback: i32,
+    pub source: Entity,
+    pub attack: Attack,
+}
+
+impl Component for Blast {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Blows up when destroyed
+#[derive(Debug)]
+pub struct Explosive {
+    pub shape: AreaShape,
+    pub damage: i32,
+    pub knockback: i32,
+}
+
+impl Component for Explosive {
+    type Storage = BTreeStorage<Self>;
+}
+
+/// Shoved `force` tiles along (dx, dy) by a heavy hit
+#[derive(Debug)]
+pub struct Knockback {
+    pub dx: i32,
+    pub dy: i32,
+    pub force: i32,
+}
+
+impl Component for Knockback {
+    type Storage = BTreeStorage<Self>;
+}
@@ -15,6 +15,51 @@ pub const MSG_X: i32 = BAR_WIDTH + 2;
 pub const MSG_WIDTH: i32 = SCREEN_WIDTH - BAR_WIDTH - 2;
 pub const MSG_HEIGHT: usize = PANEL_HEIGHT as usize - 1;
 
+pub const SIGHT_RADIUS: i32 = 8;
+
+// one item per letter in the inventory
+pub const BACKPACK_CAPACITY: usize = 26;
+
+// credits the player starts with, and what vendors have to buy with
+pub const STARTING_CREDITS: i32 = 60;
+pub const VENDOR_CREDITS: i32 = 300;
+// vendors pay this much of an item's value
+pub const SELL_PERCENT: i32 = 40;
+// how likely a level is to have a vendor, in percent
+pub const VENDOR_CHANCE: i32 = 50;
+
+// where the crafting recipes are read from, and what's used when that fails
+pub const RECIPES_FILE: &str = "data/recipes.txt";
+pub const DEFAULT_RECIPES: &str = include_str!("../data/recipes.txt");
+
+// where the verbose combat log ends up at the end of a run
+pub const COMBAT_LOG_FILE: &str = "combat_log.txt";
+
+// how monsters go about their business
+pub const WANDER_CHANCE: i32 = 10;
+pub const SEARCH_TURNS: i32 = 10;
+// tiles shooters try to keep between themselves and the player
+pub const KEEP_AWAY: i32 = 3;
+// how far out of their way shooters go to fire from cover
+pub const COVER_STEPS: f32 = 2.0;
+
+// heat generated by mech actions
+pub const MELEE_HEAT: i32 = 2;
+pub const SPRINT_HEAT: i32 = 5;
+pub const OVERHEAT_DAMAGE: i32 = 2;
+pub const SHUTDOWN_TURNS: i32 = 2;
+
+// how long explosions stay on screen
+pub const FLASH_FRAMES: i32 = 2;
+
+// damage to both sides when something is knocked into something else
+pub const COLLISION_DAMAGE: i32 = 3;
+
+// damage per stack of lingering effects
+pub const BURN_DAMAGE: i32 = 1;
+pub const BURN_HEAT: i32 = 2;
+pub const CORRODE_DAMAGE: i32 = 1;
+
 use tcod::Color;
 
 pub const COLOR_DARK_WALL: Color = Color { r: 0, g: 0, b: 100 };
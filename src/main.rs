@@ -1,23 +1,78 @@
-use specs::{Builder as _, DispatcherBuilder, World};
-
-// TODO: review all tcod usage here
-use tcod::colors;
+use specs::{DispatcherBuilder, World};
 
 mod components;
 mod consts;
+mod map;
+mod pathfinding;
 mod resources;
+mod spawner;
 mod systems;
 
-use resources::PlayerExit;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use resources::{CombatLog, DebugMode, Depth, MesLogs, PlayerExit, Recipes};
 
 fn main() {
     let mut world = World::new();
 
     world.add_resource(PlayerExit(false));
+    world.add_resource(Depth(1));
+    world.add_resource(DebugMode(std::env::args().any(|arg| arg == "--debug")));
+
+    // what unidentified items look like is different every run
+    world.add_resource(spawner::appearances());
+    world.add_resource(spawner::loot_tables());
+
+    // balancing runs record every attack, the record is saved when the game ends
+    world.add_resource(CombatLog {
+        enabled: std::env::args().any(|arg| arg == "--combat-log"),
+        records: vec![],
+    });
+
+    let map = map::make_map();
+    let start_position = map.rooms[0].center();
+    let other_rooms = map.rooms[1..].to_vec();
+    world.add_resource(map);
 
     let mut dispatcher = DispatcherBuilder::new()
         .with(systems::DebugPrint, "debug_print", &[])
-        .with(systems::Movement, "movement", &[])
+        .with(systems::PlayerControl, "player_control", &[])
+        .with(systems::InventoryMenu, "inventory", &["player_control"])
+        .with(systems::Reload, "reload", &["inventory"])
+        .with(
+            systems::ItemCollection,
+            "item_collection",
+            &["player_control"],
+        )
+        .with(systems::ItemDrop, "item_drop", &["inventory"])
+        .with(systems::LoadoutMenu, "loadout", &["player_control"])
+        .with(systems::ItemEquip, "item_equip", &["inventory", "loadout"])
+        .with(systems::CraftingMenu, "crafting", &["player_control"])
+        .with(systems::ItemCraft, "item_craft", &["crafting", "item_drop"])
+        .with(systems::Salvage, "salvage", &["player_control"])
+        .with(systems::ShopMenu, "shop", &["player_control"])
+        .with(systems::Trade, "trade", &["shop"])
+        .with(systems::Targeting, "targeting", &["player_control"])
+        .with(systems::ItemUse, "item_use", &["inventory", "targeting"])
+        .with(
+            systems::ItemIdentification,
+            "identification",
+            &["item_use", "item_equip", "item_craft"],
+        )
+        .with(systems::Movement, "movement", &["targeting"])
+        .with(systems::Visibility, "visibility", &["movement", "item_use"])
+        .with(systems::MeleeCombat, "melee_combat", &["movement"])
+        .with(systems::RangedCombat, "ranged_combat", &["melee_combat"])
+        .with(systems::Explosions, "explosions", &["ranged_combat"])
+        .with(systems::KnockbackPush, "knockback", &["explosions"])
+        .with(systems::HeatManagement, "heat", &["knockback"])
+        .with(systems::StatusTick, "status_effects", &["heat"])
+        .with(systems::Damage, "damage", &["status_effects"])
+        .with(systems::Death, "death", &["damage"])
+        .with(systems::MonsterAi, "monster_ai", &["visibility", "death"])
+        .with(systems::CombatLogView, "combat_log", &["player_control"])
+        .with(systems::LookView, "look", &["player_control"])
         .with(systems::Exit, "exit_game", &[])
         .with_thread_local(systems::TcodIntegration::default())
         .with_thread_local(systems::Input)
@@ -25,32 +80,48 @@ fn main() {
 
     dispatcher.setup(&mut world.res);
 
-    create_player(&mut world);
+    // recipes are data, so they can be tweaked without a rebuild, a broken
+    // file falls back on the recipes the game was built with
+    let recipes = Recipes::load(consts::RECIPES_FILE)
+        .and_then(|recipes| {
+            recipes.check(|name| spawner::ITEM_NAMES.contains(&name))?;
+            Ok(recipes)
+        })
+        .unwrap_or_else(|err| {
+            world
+                .write_resource::<MesLogs>()
+                .add_message(format!("Couldn't load the recipes, {}", err));
+            Recipes::parse(consts::DEFAULT_RECIPES).unwrap_or_default()
+        });
+    world.add_resource(recipes);
+
+    spawner::create_player(&mut world, start_position);
+    for &room in &other_rooms {
+        spawner::place_monsters(&mut world, room);
+        spawner::place_items(&mut world, room);
+    }
+
+    // only some levels have somebody to trade with
+    let mut rng = rand::thread_rng();
+    if rng.gen_range(0, 100) < consts::VENDOR_CHANCE {
+        if let Some(room) = other_rooms.choose(&mut rng) {
+            spawner::place_vendor(&mut world, *room);
+        }
+    }
 
     loop {
-        dispatcher.dispatch(&mut world.res);
+        dispatcher.dispatch(&world.res);
         world.maintain();
 
         if world.read_resource::<PlayerExit>().0 {
             break;
         }
     }
-}
 
-fn create_player(world: &mut World) {
-    use components::*;
-    use consts::*;
-
-    world
-        .create_entity()
-        .with(Position {
-            x: SCREEN_WIDTH / 2,
-            y: SCREEN_HEIGHT / 2,
-        })
-        .with(MapRenderable {
-            rep: '@',
-            color: colors::CYAN,
-        })
-        .with(Player {})
-        .build();
+    let combat_log = world.read_resource::<CombatLog>();
+    if combat_log.enabled {
+        if let Err(err) = combat_log.dump(consts::COMBAT_LOG_FILE) {
+            eprintln!("couldn't save the combat log: {}", err);
+        }
+    }
 }
@@ -0,0 +1,355 @@
+use rand::Rng;
+use std::cmp;
+
+use crate::consts::{MAP_HEIGHT, MAP_WIDTH};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Tile {
+    pub blocked: bool,
+    pub block_sight: bool,
+    pub explored: bool,
+}
+
+impl Tile {
+    pub fn empty() -> Self {
+        Tile {
+            blocked: false,
+            block_sight: false,
+            explored: false,
+        }
+    }
+
+    pub fn wall() -> Self {
+        Tile {
+            blocked: true,
+            block_sight: true,
+            explored: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Map {
+    pub tiles: Vec<Vec<Tile>>,
+    pub rooms: Vec<Rect>,
+}
+
+impl Map {
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < MAP_WIDTH && y < MAP_HEIGHT
+    }
+
+    pub fn tile(&self, x: i32, y: i32) -> &Tile {
+        &self.tiles[x as usize][y as usize]
+    }
+
+    pub fn tile_mut(&mut self, x: i32, y: i32) -> &mut Tile {
+        &mut self.tiles[x as usize][y as usize]
+    }
+
+    /// Out of bounds counts as blocked, so nothing can leave the map
+    pub fn blocked(&self, x: i32, y: i32) -> bool {
+        !self.in_bounds(x, y) || self.tile(x, y).blocked
+    }
+
+    /// Explored open tiles right next to unexplored ones, where exploring goes on from
+    pub fn frontier(&self) -> Vec<(i32, i32)> {
+        let mut frontier = vec![];
+        for x in 0..MAP_WIDTH {
+            for y in 0..MAP_HEIGHT {
+                let tile = self.tile(x, y);
+                if !tile.explored || tile.blocked {
+                    continue;
+                }
+                let unknown = (-1..=1).any(|dx| {
+                    (-1..=1).any(|dy| {
+                        self.in_bounds(x + dx, y + dy) && !self.tile(x + dx, y + dy).explored
+                    })
+                });
+                if unknown {
+                    frontier.push((x, y));
+                }
+            }
+        }
+        frontier
+    }
+
+    /// Open tiles with a wall right next to them, something to duck behind
+    pub fn sheltered(&self, x: i32, y: i32) -> bool {
+        !self.blocked(x, y) && (-1..=1).any(|dx| (-1..=1).any(|dy| self.blocked(x + dx, y + dy)))
+    }
+
+    pub fn block_sight(&self, x: i32, y: i32) -> bool {
+        !self.in_bounds(x, y) || self.tile(x, y).block_sight
+    }
+
+    /// Tiles a projectile flies through from `from` towards `to`, stopping at
+    /// (and including) the first wall or after `range` tiles
+    pub fn line_of_fire(&self, from: (i32, i32), to: (i32, i32), range: i32) -> Vec<(i32, i32)> {
+        let mut path = vec![];
+
+        for (x, y) in line(from, to).into_iter().skip(1) {
+            if distance(from, (x, y)) > range as f32 {
+                break;
+            }
+            path.push((x, y));
+            if self.blocked(x, y) || self.block_sight(x, y) {
+                break;
+            }
+        }
+
+        path
+    }
+
+    /// Where something fired or thrown at `to` comes down: the last open tile
+    /// along its line of fire, or `from` if there is none
+    pub fn landing(&self, from: (i32, i32), to: (i32, i32), range: i32) -> (i32, i32) {
+        self.line_of_fire(from, to, range)
+            .into_iter()
+            .rev()
+            .find(|&(x, y)| !self.blocked(x, y))
+            .unwrap_or(from)
+    }
+
+    pub fn line_of_sight(&self, from: (i32, i32), to: (i32, i32)) -> bool {
+        let points = line(from, to);
+        if points.len() <= 2 {
+            return true;
+        }
+
+        // the tiles at both ends can be walls, only those in between must be clear
+        points[1..points.len() - 1]
+            .iter()
+            .all(|&(x, y)| !self.block_sight(x, y))
+    }
+
+    /// All tiles visible from `origin` within `radius`, walls included
+    pub fn field_of_view(&self, origin: (i32, i32), radius: i32) -> Vec<(i32, i32)> {
+        let mut visible = vec![];
+
+        for y in (origin.1 - radius)..=(origin.1 + radius) {
+            for x in (origin.0 - radius)..=(origin.0 + radius) {
+                if self.in_bounds(x, y)
+                    && distance(origin, (x, y)) <= radius as f32
+                    && self.line_of_sight(origin, (x, y))
+                {
+                    visible.push((x, y));
+                }
+            }
+        }
+
+        visible
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AreaShape {
+    Burst { radius: i32 },
+    // a quarter circle opening away from its apex
+    Cone { length: i32 },
+}
+
+impl AreaShape {
+    pub fn reach(self) -> i32 {
+        match self {
+            AreaShape::Burst { radius } => radius,
+            AreaShape::Cone { length } => length,
+        }
+    }
+}
+
+impl Map {
+    /// Tiles caught in an area centered on `center`, walls shield what's behind them.
+    /// Cones point from `center` towards `toward`.
+    pub fn area(
+        &self,
+        shape: AreaShape,
+        center: (i32, i32),
+        toward: (i32, i32),
+    ) -> Vec<(i32, i32)> {
+        let tiles = self.field_of_view(center, shape.reach());
+
+        match shape {
+            AreaShape::Burst { .. } => tiles,
+            AreaShape::Cone { .. } => {
+                let (dx, dy) = (toward.0 - center.0, toward.1 - center.1);
+                let dir_len = distance(center, toward);
+                if dir_len == 0.0 {
+                    return tiles;
+                }
+
+                tiles
+                    .into_iter()
+                    .filter(|&(x, y)| {
+                        let (vx, vy) = (x - center.0, y - center.1);
+                        let len = distance(center, (x, y));
+                        // within 45 degrees either side of the direction
+                        len > 0.0
+                            && (vx * dx + vy * dy) as f32 / (len * dir_len)
+                                >= std::f32::consts::FRAC_1_SQRT_2
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+pub fn distance(a: (i32, i32), b: (i32, i32)) -> f32 {
+    let dx = b.0 - a.0;
+    let dy = b.1 - a.1;
+    ((dx.pow(2) + dy.pow(2)) as f32).sqrt()
+}
+
+/// The one of the eight neighbouring steps that points the most towards `to`
+pub fn direction(from: (i32, i32), to: (i32, i32)) -> (i32, i32) {
+    let len = distance(from, to);
+    if len == 0.0 {
+        return (0, 0);
+    }
+
+    let dx = (to.0 - from.0) as f32 / len;
+    let dy = (to.1 - from.1) as f32 / len;
+    (dx.round() as i32, dy.round() as i32)
+}
+
+/// Bresenham's line between two points, both ends included
+pub fn line(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+    let (mut x, mut y) = from;
+    let dx = (to.0 - x).abs();
+    let dy = -(to.1 - y).abs();
+    let sx = if x < to.0 { 1 } else { -1 };
+    let sy = if y < to.1 { 1 } else { -1 };
+    let mut err = dx + dy;
+
+    let mut points = vec![(x, y)];
+    while (x, y) != to {
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+        points.push((x, y));
+    }
+
+    points
+}
+
+const ROOM_MAX_SIZE: i32 = 10;
+const ROOM_MIN_SIZE: i32 = 6;
+const MAX_ROOMS: i32 = 30;
+
+pub fn make_map() -> Map {
+    let mut map = Map {
+        tiles: vec![vec![Tile::wall(); MAP_HEIGHT as usize]; MAP_WIDTH as usize],
+        rooms: vec![],
+    };
+
+    for _ in 0..MAX_ROOMS {
+        // random width and height
+        let w = rand::thread_rng().gen_range(ROOM_MIN_SIZE, ROOM_MAX_SIZE + 1);
+        let h = rand::thread_rng().gen_range(ROOM_MIN_SIZE, ROOM_MAX_SIZE + 1);
+        // random position without going out of the boundaries of the map
+        let x = rand::thread_rng().gen_range(0, MAP_WIDTH - w);
+        let y = rand::thread_rng().gen_range(0, MAP_HEIGHT - h);
+
+        let new_room = Rect::new(x, y, w, h);
+
+        // run through the other rooms and see if they intersect with this one
+        let failed = map
+            .rooms
+            .iter()
+            .any(|other_room| new_room.intersects_with(other_room));
+
+        if !failed {
+            // this means there are no intersections, so this room is valid
+
+            // "paint" it to the map's tiles
+            create_room(new_room, &mut map);
+
+            // all rooms after the first are connected to the previous room with a tunnel
+            if let Some(prev_room) = map.rooms.last() {
+                let (new_x, new_y) = new_room.center();
+                let (prev_x, prev_y) = prev_room.center();
+
+                // draw a coin (random bool value -- either true or false)
+                if rand::random() {
+                    // first move horizontally, then vertically
+                    create_h_tunnel(prev_x, new_x, prev_y, &mut map);
+                    create_v_tunnel(prev_y, new_y, new_x, &mut map);
+                } else {
+                    // first move vertically, then horizontally
+                    create_v_tunnel(prev_y, new_y, prev_x, &mut map);
+                    create_h_tunnel(prev_x, new_x, new_y, &mut map);
+                }
+            }
+
+            map.rooms.push(new_room);
+        }
+    }
+
+    map
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Rect {
+    x1: i32,
+    y1: i32,
+    x2: i32,
+    y2: i32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, w: i32, h: i32) -> Self {
+        Rect {
+            x1: x,
+            y1: y,
+            x2: x + w,
+            y2: y + h,
+        }
+    }
+
+    pub fn center(&self) -> (i32, i32) {
+        let center_x = (self.x1 + self.x2) / 2;
+        let center_y = (self.y1 + self.y2) / 2;
+        (center_x, center_y)
+    }
+
+    pub fn intersects_with(&self, other: &Rect) -> bool {
+        // returns true if this rectangle intersects with another one
+        (self.x1 <= other.x2)
+            && (self.x2 >= other.x1)
+            && (self.y1 <= other.y2)
+            && (self.y2 >= other.y1)
+    }
+
+    pub fn rand_inside(&self) -> (i32, i32) {
+        let x = rand::thread_rng().gen_range(self.x1 + 1, self.x2);
+        let y = rand::thread_rng().gen_range(self.y1 + 1, self.y2);
+
+        (x, y)
+    }
+}
+
+fn create_room(room: Rect, map: &mut Map) {
+    for x in (room.x1 + 1)..room.x2 {
+        for y in (room.y1 + 1)..room.y2 {
+            *map.tile_mut(x, y) = Tile::empty();
+        }
+    }
+}
+
+fn create_h_tunnel(x1: i32, x2: i32, y: i32, map: &mut Map) {
+    for x in cmp::min(x1, x2)..=cmp::max(x1, x2) {
+        *map.tile_mut(x, y) = Tile::empty();
+    }
+}
+
+fn create_v_tunnel(y1: i32, y2: i32, x: i32, map: &mut Map) {
+    for y in cmp::min(y1, y2)..=cmp::max(y1, y2) {
+        *map.tile_mut(x, y) = Tile::empty();
+    }
+}
@@ -0,0 +1,456 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::consts::{MAP_HEIGHT, MAP_WIDTH};
+use crate::map::Map;
+
+// more than enough to cross the whole map, any search past it is a lost cause
+const MAX_EXPANDED: usize = (MAP_WIDTH * MAP_HEIGHT) as usize;
+
+type StepCost<'a> = Box<dyn Fn((i32, i32)) -> i32 + 'a>;
+
+/// A* over the map, in the eight directions things can move in.
+/// Walls always block, anything else in the way and what each step
+/// costs is up to whoever asks.
+pub struct Pathfinder<'a> {
+    map: &'a Map,
+    occupied: HashSet<(i32, i32)>,
+    cost: Option<StepCost<'a>>,
+}
+
+impl<'a> Pathfinder<'a> {
+    pub fn new(map: &'a Map) -> Self {
+        Pathfinder {
+            map,
+            occupied: HashSet::new(),
+            cost: None,
+        }
+    }
+
+    /// Tiles that count as blocked too, usually where other entities stand.
+    /// The goal is always allowed, there's often somebody standing on it.
+    pub fn avoiding<I: IntoIterator<Item = (i32, i32)>>(mut self, tiles: I) -> Self {
+        self.occupied.extend(tiles);
+        self
+    }
+
+    /// What stepping onto each tile costs, 1 when not given. Costs below 1
+    /// are taken as 1, so the search stays admissible.
+    pub fn costing<F: Fn((i32, i32)) -> i32 + 'a>(mut self, cost: F) -> Self {
+        self.cost = Some(Box::new(cost));
+        self
+    }
+
+    fn step_cost(&self, at: (i32, i32)) -> i32 {
+        self.cost.as_ref().map_or(1, |cost| cost(at).max(1))
+    }
+
+    fn passable(&self, at: (i32, i32), goal: (i32, i32)) -> bool {
+        !self.map.blocked(at.0, at.1) && (at == goal || !self.occupied.contains(&at))
+    }
+
+    /// The steps from `from` to `to`, not including `from` itself.
+    /// None when there's no way through.
+    pub fn path(&self, from: (i32, i32), to: (i32, i32)) -> Option<Vec<(i32, i32)>> {
+        if from == to {
+            return Some(vec![]);
+        }
+        if self.map.blocked(to.0, to.1) {
+            return None;
+        }
+
+        let mut open = BinaryHeap::new();
+        let mut came_from = HashMap::new();
+        let mut best = HashMap::new();
+        let mut closed = HashSet::new();
+
+        best.insert(from, 0);
+        // ties go to whatever is closer to the goal, which keeps paths straight
+        open.push(Reverse((heuristic(from, to), heuristic(from, to), from)));
+
+        while let Some(Reverse((_, _, at))) = open.pop() {
+            if at == to {
+                return Some(unwind(&came_from, from, to));
+            }
+            if !closed.insert(at) {
+                continue;
+            }
+            if closed.len() > MAX_EXPANDED {
+                return None;
+            }
+
+            let cost_here = best[&at];
+            for next in neighbours(at) {
+                if closed.contains(&next) || !self.passable(next, to) {
+                    continue;
+                }
+
+                let cost = cost_here + self.step_cost(next);
+                if best.get(&next).is_some_and(|&known| known <= cost) {
+                    continue;
+                }
+                best.insert(next, cost);
+                came_from.insert(next, at);
+                let h = heuristic(next, to);
+                open.push(Reverse((cost + h, h, next)));
+            }
+        }
+
+        None
+    }
+}
+
+// how much further than the threat a fleeing map sends things, the extra makes
+// cornered monsters run past the threat rather than into a dead end
+const FLEE_COEFFICIENT: f32 = -1.2;
+
+/// How far every tile is from the nearest of any number of goals. Rolling
+/// downhill from anywhere leads to a goal, uphill away from them.
+#[derive(Clone, Debug)]
+pub struct DijkstraMap {
+    // per tile, None where it can't be reached or crossed
+    values: Vec<Option<f32>>,
+    passable: Vec<bool>,
+}
+
+impl DijkstraMap {
+    /// `walkable` narrows down what can be crossed on top of the map's walls
+    pub fn new<F: Fn((i32, i32)) -> bool>(map: &Map, goals: &[(i32, i32)], walkable: F) -> Self {
+        let passable = tiles()
+            .map(|(x, y)| !map.blocked(x, y) && walkable((x, y)))
+            .collect();
+        let mut seeds = vec![None; tile_count()];
+        for &(x, y) in goals.iter().filter(|&&(x, y)| map.in_bounds(x, y)) {
+            seeds[index((x, y))] = Some(0.0);
+        }
+        DijkstraMap::scan(seeds, passable)
+    }
+
+    pub fn value(&self, at: (i32, i32)) -> Option<f32> {
+        if at.0 < 0 || at.1 < 0 || at.0 >= MAP_WIDTH || at.1 >= MAP_HEIGHT {
+            return None;
+        }
+        self.values[index(at)]
+    }
+
+    /// Every map scaled by its weight and added up, tiles any of them can't
+    /// reach are left out. Not rescanned, so downhill may stop short.
+    pub fn weighted_sum(layers: &[(&DijkstraMap, f32)]) -> Option<DijkstraMap> {
+        let (first, _) = layers.first()?;
+        let values = (0..tile_count())
+            .map(|i| {
+                layers
+                    .iter()
+                    .map(|(layer, weight)| layer.values[i].map(|v| v * weight))
+                    .sum()
+            })
+            .collect();
+        Some(DijkstraMap {
+            values,
+            passable: first.passable.clone(),
+        })
+    }
+
+    /// A map for running away from this one's goals. Rescanned, so going
+    /// downhill finds ways out instead of dead ends right next to the goals.
+    pub fn inverted(&self) -> DijkstraMap {
+        let flipped = DijkstraMap::weighted_sum(&[(self, FLEE_COEFFICIENT)])
+            .map_or_else(|| self.values.clone(), |m| m.values);
+        DijkstraMap::scan(flipped, self.passable.clone())
+    }
+
+    /// The neighbour of `from` furthest downhill, skipping anything `occupied`
+    /// says is taken. None when `from` is already as low as it gets.
+    pub fn downhill<F: Fn((i32, i32)) -> bool>(
+        &self,
+        from: (i32, i32),
+        occupied: F,
+    ) -> Option<(i32, i32)> {
+        let here = self.value(from).unwrap_or(f32::MAX);
+        neighbours(from)
+            .filter(|&next| !occupied(next))
+            .filter_map(|next| Some((next, self.value(next)?)))
+            .filter(|&(_, value)| value < here)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+            .map(|(next, _)| next)
+    }
+
+    // Dijkstra from every seeded tile at once, each starting at its own value
+    fn scan(seeds: Vec<Option<f32>>, passable: Vec<bool>) -> DijkstraMap {
+        let mut values = vec![None; tile_count()];
+        let mut open: BinaryHeap<_> = tiles()
+            .filter_map(|at| Some(Lowest(seeds[index(at)]?, at)))
+            .filter(|Lowest(_, at)| passable[index(*at)])
+            .collect();
+
+        while let Some(Lowest(value, at)) = open.pop() {
+            if values[index(at)].is_some() {
+                continue;
+            }
+            values[index(at)] = Some(value);
+
+            for next in neighbours(at) {
+                let inside =
+                    next.0 >= 0 && next.1 >= 0 && next.0 < MAP_WIDTH && next.1 < MAP_HEIGHT;
+                if inside && passable[index(next)] && values[index(next)].is_none() {
+                    open.push(Lowest(value + 1.0, next));
+                }
+            }
+        }
+
+        DijkstraMap { values, passable }
+    }
+}
+
+// a heap entry that pops the lowest value first
+#[derive(PartialEq)]
+struct Lowest(f32, (i32, i32));
+
+impl Eq for Lowest {}
+
+impl Ord for Lowest {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.partial_cmp(&self.0).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Lowest {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn tiles() -> impl Iterator<Item = (i32, i32)> {
+    (0..MAP_WIDTH).flat_map(|x| (0..MAP_HEIGHT).map(move |y| (x, y)))
+}
+
+fn tile_count() -> usize {
+    (MAP_WIDTH * MAP_HEIGHT) as usize
+}
+
+// laid out the same way `tiles` goes through them
+fn index((x, y): (i32, i32)) -> usize {
+    (x * MAP_HEIGHT + y) as usize
+}
+
+// diagonal steps cost the same as straight ones
+fn heuristic(a: (i32, i32), b: (i32, i32)) -> i32 {
+    (a.0 - b.0).abs().max((a.1 - b.1).abs())
+}
+
+fn neighbours((x, y): (i32, i32)) -> impl Iterator<Item = (i32, i32)> {
+    (-1..=1)
+        .flat_map(move |dx| (-1..=1).map(move |dy| (dx, dy)))
+        .filter(|&step| step != (0, 0))
+        .map(move |(dx, dy)| (x + dx, y + dy))
+}
+
+fn unwind(
+    came_from: &HashMap<(i32, i32), (i32, i32)>,
+    from: (i32, i32),
+    to: (i32, i32),
+) -> Vec<(i32, i32)> {
+    let mut path = vec![to];
+    let mut at = to;
+    while let Some(&previous) = came_from.get(&at) {
+        if previous == from {
+            break;
+        }
+        path.push(previous);
+        at = previous;
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Tile;
+
+    // '#' is a wall, anything else is floor, everything outside the drawing is wall
+    fn map_of(rows: &[&str]) -> Map {
+        let mut map = Map {
+            tiles: vec![vec![Tile::wall(); MAP_HEIGHT as usize]; MAP_WIDTH as usize],
+            rooms: vec![],
+        };
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c != '#' {
+                    *map.tile_mut(x as i32, y as i32) = Tile::empty();
+                }
+            }
+        }
+        map
+    }
+
+    // every step has to be a single move onto open ground
+    fn assert_walkable(map: &Map, from: (i32, i32), path: &[(i32, i32)]) {
+        let mut at = from;
+        for &step in path {
+            assert!(
+                heuristic(at, step) == 1,
+                "{:?} -> {:?} isn't one step",
+                at,
+                step
+            );
+            assert!(!map.blocked(step.0, step.1), "{:?} is a wall", step);
+            at = step;
+        }
+    }
+
+    #[test]
+    fn straight_line_in_the_open() {
+        let map = map_of(&["#######", "#.....#", "#######"]);
+        let path = Pathfinder::new(&map).path((1, 1), (5, 1)).unwrap();
+        assert_eq!(path, vec![(2, 1), (3, 1), (4, 1), (5, 1)]);
+    }
+
+    #[test]
+    fn standing_on_the_goal_is_an_empty_path() {
+        let map = map_of(&["###", "#.#", "###"]);
+        assert_eq!(Pathfinder::new(&map).path((1, 1), (1, 1)), Some(vec![]));
+    }
+
+    #[test]
+    fn goes_around_a_wall_corner() {
+        // the straight line runs into the wall in the middle
+        let map = map_of(&[
+            "#######", //
+            "#.....#", //
+            "#.###.#", //
+            "#.#...#", //
+            "#######",
+        ]);
+        let from = (1, 3);
+        let path = Pathfinder::new(&map).path(from, (3, 3)).unwrap();
+        assert_walkable(&map, from, &path);
+        assert_eq!(path.last(), Some(&(3, 3)));
+        assert_eq!(path.len(), 7);
+    }
+
+    #[test]
+    fn no_path_into_a_sealed_room() {
+        let map = map_of(&[
+            "#######", //
+            "#..#..#", //
+            "#..#..#", //
+            "#######",
+        ]);
+        assert_eq!(Pathfinder::new(&map).path((1, 1), (5, 2)), None);
+        assert_eq!(Pathfinder::new(&map).path((1, 1), (3, 1)), None);
+    }
+
+    #[test]
+    fn entities_block_only_when_asked() {
+        let map = map_of(&["#####", "#...#", "#####"]);
+        let finder = Pathfinder::new(&map);
+        assert!(finder.path((1, 1), (3, 1)).is_some());
+
+        let blocked = Pathfinder::new(&map).avoiding(vec![(2, 1)]);
+        assert_eq!(blocked.path((1, 1), (3, 1)), None);
+    }
+
+    #[test]
+    fn an_occupied_goal_is_still_reachable() {
+        let map = map_of(&["#####", "#...#", "#####"]);
+        let finder = Pathfinder::new(&map).avoiding(vec![(3, 1)]);
+        assert_eq!(finder.path((1, 1), (3, 1)), Some(vec![(2, 1), (3, 1)]));
+    }
+
+    #[test]
+    fn expensive_tiles_are_avoided_when_cheaper_ways_exist() {
+        // '~' is rough ground, the way around it is longer but cheaper
+        let rows = [
+            "#######", //
+            "#.~~~.#", //
+            "#.....#", //
+            "#######",
+        ];
+        let map = map_of(&rows);
+        let rough = |(x, y): (i32, i32)| {
+            let row = rows.get(y as usize)?;
+            row.chars().nth(x as usize)
+        };
+        let from = (1, 1);
+        let path = Pathfinder::new(&map)
+            .costing(|at| if rough(at) == Some('~') { 10 } else { 1 })
+            .path(from, (5, 1))
+            .unwrap();
+
+        assert_walkable(&map, from, &path);
+        assert!(path.iter().all(|&at| rough(at) != Some('~')));
+        assert_eq!(path.last(), Some(&(5, 1)));
+    }
+
+    #[test]
+    fn dijkstra_counts_steps_to_the_nearest_goal() {
+        let map = map_of(&["#########", "#.......#", "#########"]);
+        let dijkstra = DijkstraMap::new(&map, &[(1, 1), (7, 1)], |_| true);
+        assert_eq!(dijkstra.value((1, 1)), Some(0.0));
+        assert_eq!(dijkstra.value((3, 1)), Some(2.0));
+        assert_eq!(dijkstra.value((4, 1)), Some(3.0));
+        assert_eq!(dijkstra.value((6, 1)), Some(1.0));
+        assert_eq!(dijkstra.value((0, 0)), None);
+    }
+
+    #[test]
+    fn dijkstra_leaves_out_what_isnt_walkable() {
+        let map = map_of(&["#######", "#.....#", "#######"]);
+        let dijkstra = DijkstraMap::new(&map, &[(1, 1)], |(x, _)| x != 3);
+        assert_eq!(dijkstra.value((2, 1)), Some(1.0));
+        assert_eq!(dijkstra.value((3, 1)), None);
+        assert_eq!(dijkstra.value((5, 1)), None);
+    }
+
+    #[test]
+    fn downhill_leads_to_a_goal() {
+        let map = map_of(&[
+            "#######", //
+            "#.....#", //
+            "#.###.#", //
+            "#.#...#", //
+            "#######",
+        ]);
+        let dijkstra = DijkstraMap::new(&map, &[(3, 3)], |_| true);
+        let mut at = (1, 3);
+        for _ in 0..10 {
+            match dijkstra.downhill(at, |_| false) {
+                Some(next) => at = next,
+                None => break,
+            }
+        }
+        assert_eq!(at, (3, 3));
+    }
+
+    #[test]
+    fn weighted_sums_scale_and_add_up() {
+        let map = map_of(&["######", "#....#", "######"]);
+        let left = DijkstraMap::new(&map, &[(1, 1)], |_| true);
+        let right = DijkstraMap::new(&map, &[(4, 1)], |_| true);
+        let sum = DijkstraMap::weighted_sum(&[(&left, 1.0), (&right, 0.5)]).unwrap();
+        assert_eq!(sum.value((1, 1)), Some(1.5));
+        assert_eq!(sum.value((4, 1)), Some(3.0));
+    }
+
+    #[test]
+    fn fleeing_runs_away_from_the_goal() {
+        let map = map_of(&["#########", "#.......#", "#########"]);
+        let flee = DijkstraMap::new(&map, &[(2, 1)], |_| true).inverted();
+        assert_eq!(flee.downhill((3, 1), |_| false), Some((4, 1)));
+        assert_eq!(flee.downhill((7, 1), |_| false), None);
+    }
+
+    #[test]
+    fn diagonals_cost_the_same_as_straight_steps() {
+        let map = map_of(&[
+            "######", //
+            "#....#", //
+            "#....#", //
+            "#....#", //
+            "######",
+        ]);
+        let path = Pathfinder::new(&map).path((1, 1), (4, 3)).unwrap();
+        assert_eq!(path.len(), 3);
+    }
+}
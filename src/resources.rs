@@ -1,3 +1,15 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::iter;
+use std::path::Path;
+
+use rand::Rng;
+use specs::Entity;
+
+use crate::components::Attack;
+use crate::consts::MSG_HEIGHT;
+
 #[derive(Debug, Default)]
 pub struct InputMapping {
     pub key: Option<VirtualKey>,
@@ -11,9 +23,32 @@ pub enum VirtualKey {
     MoveDown,
     MoveLeft,
     MoveRight,
+    SprintUp,
+    SprintDown,
+    SprintLeft,
+    SprintRight,
     Exit,
     PickItem,
     DropItem,
+    Fire,
+    Reload,
+    Repair,
+    Wait,
+    NextTarget,
+    Confirm,
+    Cancel,
+    CombatLog,
+    Inventory,
+    Loadout,
+    Look,
+    Salvage,
+    Crafting,
+    Trade,
+    Explore,
+    // any letter typed while a menu is open
+    Letter(char),
+    // any digit typed while a menu is open
+    Digit(i32),
 }
 
 #[derive(Debug, Default)]
@@ -22,5 +57,440 @@ pub struct MesLogs {
     pub logs: Vec<String>,
 }
 
+impl MesLogs {
+    pub fn add_message<T: Into<String>>(&mut self, message: T) {
+        // if the buffer is full, remove the first message to make room for the new one
+        if self.messages.len() == MSG_HEIGHT {
+            self.messages.remove(0);
+        }
+        self.messages.push(message.into());
+    }
+}
+
+/// Everything that went into one attack, for balancing
+#[derive(Debug)]
+pub struct AttackRecord {
+    pub attacker: String,
+    pub target: String,
+    pub attack: Attack,
+    // None for attacks that never landed
+    pub outcome: Option<HitOutcome>,
+}
+
+#[derive(Debug)]
+pub struct HitOutcome {
+    pub base: i32,
+    pub damage_type: &'static str,
+    pub multiplier: f32,
+    pub location: Option<&'static str>,
+    pub absorbed: i32,
+    pub damage: i32,
+}
+
+impl AttackRecord {
+    pub fn describe(&self) -> String {
+        let attack = &self.attack;
+        let mut text = format!("{} -> {} [{}]", self.attacker, self.target, attack.weapon);
+
+        if let Some((roll, chance)) = attack.roll {
+            text += &format!(" roll {}/{}", roll, chance);
+        }
+        if !attack.modifiers.is_empty() {
+            let modifiers: Vec<_> = attack
+                .modifiers
+                .iter()
+                .map(|(name, value)| format!("{} {:+}", name, value))
+                .collect();
+            text += &format!(" ({})", modifiers.join(", "));
+        }
+
+        match &self.outcome {
+            None => text + ": miss",
+            Some(hit) => {
+                text += &format!(": {} {} x{:.2}", hit.base, hit.damage_type, hit.multiplier);
+                if let Some(location) = hit.location {
+                    text += &format!(" @{}", location);
+                }
+                if hit.absorbed > 0 {
+                    text += &format!(" -{} armor", hit.absorbed);
+                }
+                text + &format!(" = {}", hit.damage)
+            }
+        }
+    }
+}
+
+/// The verbose combat log, only filled in when turned on
+#[derive(Debug, Default)]
+pub struct CombatLog {
+    pub enabled: bool,
+    pub records: Vec<AttackRecord>,
+}
+
+impl CombatLog {
+    pub fn record(&mut self, record: AttackRecord) {
+        if self.enabled {
+            self.records.push(record);
+        }
+    }
+
+    pub fn dump<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let lines: Vec<_> = self
+            .records
+            .iter()
+            .enumerate()
+            .map(|(i, r)| format!("{:5} {}\n", i + 1, r.describe()))
+            .collect();
+        fs::write(path, lines.concat())
+    }
+}
+
+/// Makes `output` out of `inputs`, as (quantity, item name)
+#[derive(Debug)]
+pub struct Recipe {
+    pub output: (i32, String),
+    pub inputs: Vec<(i32, String)>,
+}
+
+impl Recipe {
+    pub fn describe(&self) -> String {
+        let inputs: Vec<_> = self
+            .inputs
+            .iter()
+            .map(|(quantity, name)| format!("{} {}", quantity, name))
+            .collect();
+        format!(
+            "{} {} <- {}",
+            self.output.0,
+            self.output.1,
+            inputs.join(", ")
+        )
+    }
+}
+
+/// Everything that can be crafted, loaded from a data file at startup
+#[derive(Debug, Default)]
+pub struct Recipes(pub Vec<Recipe>);
+
+impl Recipes {
+    /// One recipe per line, written like "20 autocannon shells <- 2 scrap, 1 servo".
+    /// Blank lines and lines starting with '#' are skipped.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Recipes::parse(&fs::read_to_string(path)?)
+    }
+
+    /// The same as `load`, for text that's already at hand
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut recipes = vec![];
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let recipe = parse_recipe(line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("bad recipe on line {}: {}", i + 1, line),
+                )
+            })?;
+            recipes.push(recipe);
+        }
+
+        Ok(Recipes(recipes))
+    }
+
+    /// Fails on the first item name `known` doesn't recognise, so a typo can't
+    /// use up the ingredients and make nothing
+    pub fn check<F: FnMut(&str) -> bool>(&self, mut known: F) -> io::Result<()> {
+        for recipe in &self.0 {
+            for (_, name) in iter::once(&recipe.output).chain(&recipe.inputs) {
+                if !known(name) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unknown item \"{}\" in: {}", name, recipe.describe()),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_recipe(line: &str) -> Option<Recipe> {
+    let mut sides = line.split("<-");
+    let output = parse_quantity(sides.next()?)?;
+    let inputs = sides
+        .next()?
+        .split(',')
+        .map(parse_quantity)
+        .collect::<Option<Vec<_>>>()?;
+
+    if sides.next().is_some() {
+        return None;
+    }
+    Some(Recipe { output, inputs })
+}
+
+// "3 scrap" -> (3, "scrap")
+fn parse_quantity(text: &str) -> Option<(i32, String)> {
+    let (quantity, name) = text.trim().split_once(' ')?;
+    let quantity = quantity.parse().ok().filter(|&q| q > 0)?;
+    Some((quantity, name.trim().to_string()))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum LootEntry {
+    Nothing,
+    // (item name, quantity)
+    Item(&'static str, i32),
+    // rolls again on another table
+    Table(&'static str),
+}
+
+/// Weighted tables of what monsters leave behind, by name
+#[derive(Debug, Default)]
+pub struct LootTables(pub HashMap<&'static str, Vec<(i32, LootEntry)>>);
+
+// deep enough for any sane nesting, and stops tables that refer to each other
+const MAX_LOOT_DEPTH: usize = 8;
+
+impl LootTables {
+    /// Rolls on `table`, following nested tables down to an item, if any
+    pub fn roll<R: Rng>(&self, table: &str, rng: &mut R) -> Option<(&'static str, i32)> {
+        let mut table = table;
+        for _ in 0..MAX_LOOT_DEPTH {
+            let entries = self.0.get(table)?;
+            let total: i32 = entries.iter().map(|(weight, _)| weight).sum();
+            if total <= 0 {
+                return None;
+            }
+
+            let mut roll = rng.gen_range(0, total);
+            let entry = entries.iter().find_map(|&(weight, entry)| {
+                if roll < weight {
+                    Some(entry)
+                } else {
+                    roll -= weight;
+                    None
+                }
+            })?;
+
+            match entry {
+                LootEntry::Nothing => return None,
+                LootEntry::Item(name, quantity) => return Some((name, quantity)),
+                LootEntry::Table(next) => table = next,
+            }
+        }
+        None
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct PlayerExit(pub bool);
+
+/// How deep the current level is, starting from 1; there's only the one level for now
+#[derive(Debug)]
+pub struct Depth(pub i32);
+
+impl Default for Depth {
+    fn default() -> Self {
+        Depth(1)
+    }
+}
+
+/// Turned on with `--debug`, prints what's going on behind the scenes
+#[derive(Debug, Default)]
+pub struct DebugMode(pub bool);
+
+/// Whether the player spent a turn this frame, everything else acts on it
+#[derive(Debug, Default)]
+pub struct PlayerTurn(pub bool);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum GameMode {
+    #[default]
+    Normal,
+    // aiming the player's ranged weapon at the reticle, or the item about to be thrown
+    Targeting {
+        x: i32,
+        y: i32,
+        item: Option<Entity>,
+    },
+    // reading the combat log, scrolled back this many records
+    CombatLog {
+        scroll: usize,
+    },
+    // looking through the backpack, `dropping` drops the picked item right away
+    Inventory {
+        selected: Option<usize>,
+        dropping: bool,
+    },
+    // asking how many of a stack to drop, `typed` is 0 until a digit is typed
+    DropAmount {
+        item: Entity,
+        typed: i32,
+    },
+    // looking at whatever is under the cursor
+    Look {
+        x: i32,
+        y: i32,
+    },
+    // picking a recipe to craft
+    Crafting,
+    // looking over the mech's hardpoints, picking one takes off what's mounted there
+    Loadout,
+    // trading with a vendor, either buying its stock or selling the player's
+    Shop {
+        vendor: Entity,
+        selling: bool,
+    },
+}
+
+/// What the player found out about unidentified items this run
+#[derive(Debug, Default)]
+pub struct Identification {
+    // real name -> what it looks like until identified, shuffled every run
+    pub appearances: HashMap<String, String>,
+    pub known: HashSet<String>,
+    // identified since names were last updated, as (appearance, real name)
+    pub fresh: Vec<(String, String)>,
+}
+
+impl Identification {
+    /// What items called `real` go by, as long as they're still unknown
+    pub fn appearance(&self, real: &str) -> Option<&str> {
+        if self.known.contains(real) {
+            return None;
+        }
+        self.appearances.get(real).map(String::as_str)
+    }
+
+    pub fn learn(&mut self, real: &str) {
+        if let Some(appearance) = self.appearance(real).map(str::to_string) {
+            self.known.insert(real.to_string());
+            self.fresh.push((appearance, real.to_string()));
+        }
+    }
+}
+
+/// Tiles lit up by explosions, shown for a few frames
+#[derive(Debug, Default)]
+pub struct Flashes {
+    pub tiles: Vec<(i32, i32)>,
+    pub frames: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn parses_a_recipe() {
+        let recipe = parse_recipe("4 missiles <- 3 scrap, 1 servo").unwrap();
+        assert_eq!(recipe.output, (4, "missiles".to_string()));
+        assert_eq!(
+            recipe.inputs,
+            vec![(3, "scrap".to_string()), (1, "servo".to_string())]
+        );
+    }
+
+    #[test]
+    fn rejects_a_recipe_without_an_arrow() {
+        assert!(parse_recipe("4 missiles 3 scrap").is_none());
+    }
+
+    #[test]
+    fn rejects_a_recipe_with_two_arrows() {
+        assert!(parse_recipe("4 missiles <- 3 scrap <- 1 servo").is_none());
+    }
+
+    #[test]
+    fn rejects_zero_and_missing_quantities() {
+        assert!(parse_quantity("0 scrap").is_none());
+        assert!(parse_quantity("-2 scrap").is_none());
+        assert!(parse_quantity("scrap").is_none());
+        assert!(parse_recipe("1 armor plating <- 0 scrap").is_none());
+    }
+
+    #[test]
+    fn reports_the_bad_line() {
+        let err = Recipes::parse("# ammo\n20 autocannon shells <- 2 scrap\n\noops\n").unwrap_err();
+        assert!(err.to_string().contains("line 4"));
+    }
+
+    #[test]
+    fn checks_item_names() {
+        let recipes = Recipes::parse("1 repair kit <- 2 scrap, 1 srevo").unwrap();
+        assert!(recipes.check(|_| true).is_ok());
+        let err = recipes.check(|name| name != "srevo").unwrap_err();
+        assert!(err.to_string().contains("srevo"));
+    }
+
+    #[test]
+    fn built_in_recipes_parse() {
+        assert!(!Recipes::parse(crate::consts::DEFAULT_RECIPES)
+            .unwrap()
+            .0
+            .is_empty());
+    }
+
+    fn tables(tables: Vec<(&'static str, Vec<(i32, LootEntry)>)>) -> LootTables {
+        LootTables(tables.into_iter().collect())
+    }
+
+    #[test]
+    fn rolls_through_nested_tables() {
+        use LootEntry::*;
+        let loot = tables(vec![
+            ("outer", vec![(1, Table("inner"))]),
+            ("inner", vec![(1, Item("scrap", 3))]),
+        ]);
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(loot.roll("outer", &mut rng), Some(("scrap", 3)));
+    }
+
+    #[test]
+    fn rolls_nothing() {
+        let loot = tables(vec![("empty", vec![(1, LootEntry::Nothing)])]);
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(loot.roll("empty", &mut rng), None);
+    }
+
+    #[test]
+    fn rolls_nothing_on_zero_weights_or_unknown_tables() {
+        let loot = tables(vec![("weightless", vec![(0, LootEntry::Item("scrap", 1))])]);
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(loot.roll("weightless", &mut rng), None);
+        assert_eq!(loot.roll("missing", &mut rng), None);
+    }
+
+    #[test]
+    fn stops_tables_that_refer_to_themselves() {
+        let loot = tables(vec![("loop", vec![(1, LootEntry::Table("loop"))])]);
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(loot.roll("loop", &mut rng), None);
+    }
+
+    #[test]
+    fn follows_tables_down_to_the_depth_limit() {
+        const CHAIN: [&str; MAX_LOOT_DEPTH + 1] = ["0", "1", "2", "3", "4", "5", "6", "7", "8"];
+        let mut rng = StdRng::seed_from_u64(7);
+
+        // a chain as long as the limit still gets to the item, one more doesn't
+        let chain = |depth: usize| {
+            let mut loot: Vec<_> = (0..depth - 1)
+                .map(|i| (CHAIN[i], vec![(1, LootEntry::Table(CHAIN[i + 1]))]))
+                .collect();
+            loot.push((CHAIN[depth - 1], vec![(1, LootEntry::Item("scrap", 1))]));
+            tables(loot)
+        };
+        assert_eq!(
+            chain(MAX_LOOT_DEPTH).roll("0", &mut rng),
+            Some(("scrap", 1))
+        );
+        assert_eq!(chain(MAX_LOOT_DEPTH + 1).roll("0", &mut rng), None);
+    }
+}
@@ -0,0 +1,1124 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use specs::{join::Join, Builder, Entity, EntityBuilder, World};
+
+// TODO: review all tcod usage here
+use tcod::colors;
+
+use crate::components::*;
+use crate::consts::*;
+use crate::map::{AreaShape, Rect};
+use crate::resources::{Depth, Identification, LootEntry, LootTables};
+
+pub fn create_player(world: &mut World, (x, y): (i32, i32)) {
+    let player = world
+        .create_entity()
+        .with(Position { x, y })
+        .with(MapRenderable {
+            rep: '@',
+            color: colors::CYAN,
+        })
+        .with(Player {})
+        .with(Name("aquarhead".to_string()))
+        .with(HitPoints::new(30))
+        .with(Melee {
+            attack: 5,
+            defense: 0,
+            damage_type: DamageType::Kinetic,
+            on_hit: None,
+        })
+        .with(MechBody::new(vec![
+            MechPart::new(PartKind::Torso, 12, 30),
+            MechPart::new(PartKind::LeftArm, 6, 12),
+            MechPart::new(PartKind::RightArm, 6, 12),
+            MechPart::new(PartKind::Legs, 8, 16),
+            MechPart::new(PartKind::Sensors, 2, 6),
+        ]))
+        .with(Heat::new(30, 3))
+        .with(Wallet {
+            credits: STARTING_CREDITS,
+        })
+        .with(Viewshed::new(SIGHT_RADIUS))
+        .with(Hardpoints {
+            slots: vec![
+                (Hardpoint::LeftArm, MountSize::Medium),
+                (Hardpoint::RightArm, MountSize::Heavy),
+                (Hardpoint::Shoulder, MountSize::Light),
+                (Hardpoint::Torso, MountSize::Heavy),
+                (Hardpoint::Legs, MountSize::Medium),
+            ],
+        })
+        .build();
+
+    // the gun starts out mounted, with its workings on the mech
+    let gun = autocannon(world)
+        .with(Equipped {
+            owner: player,
+            slot: Hardpoint::RightArm,
+        })
+        .build();
+    {
+        let mut weapons = world.write_storage::<RangedWeapon>();
+        if let Some(weapon) = weapons.remove(gun) {
+            let _ = weapons.insert(player, weapon);
+        }
+    }
+
+    // spare ammo to start with, and some plating to patch the mech up with
+    for (name, quantity) in [("autocannon shells", 40), ("repair kit", 2)] {
+        if let Some(item) = item_stack(world, name, quantity) {
+            let _ = world
+                .write_storage::<InBackpack>()
+                .insert(item, InBackpack { owner: player });
+        }
+    }
+}
+
+const MAX_ROOM_MONSTERS: i32 = 3;
+
+pub fn place_monsters(world: &mut World, room: Rect) {
+    // choose random number of monsters
+    let num_monsters = rand::thread_rng().gen_range(0, MAX_ROOM_MONSTERS + 1);
+
+    let mut monsters_pos = vec![];
+
+    for _ in 0..num_monsters {
+        // choose random spot for this monster
+        let (x, y) = room.rand_inside();
+        if monsters_pos.contains(&(x, y)) {
+            continue;
+        }
+        monsters_pos.push((x, y));
+
+        let builder = world.create_entity_unchecked().with(Position { x, y });
+
+        let roll = rand::thread_rng().gen_range(0, 100);
+        match roll {
+            0..=44 => orc(builder),
+            45..=59 => troll(builder),
+            60..=74 => scout_mech(world, builder),
+            75..=84 => rocket_mech(world, builder),
+            _ => bomb_drone(builder),
+        }
+    }
+}
+
+const VENDOR_STOCK: i32 = 8;
+
+/// A trader somewhere in `room`, stocked for the current depth
+pub fn place_vendor(world: &mut World, room: Rect) {
+    // anywhere nothing else is standing
+    let spot = (0..20).map(|_| room.rand_inside()).find(|&(x, y)| {
+        let pos = world.read_storage::<Position>();
+        let hp = world.read_storage::<HitPoints>();
+        !(&pos, &hp).join().any(|(p, _)| p.x == x && p.y == y)
+    });
+    let (x, y) = match spot {
+        Some(spot) => spot,
+        None => return,
+    };
+
+    let vendor = world
+        .create_entity()
+        .with(Position { x, y })
+        .with(MapRenderable {
+            rep: '@',
+            color: colors::GOLD,
+        })
+        .with(Name("trader".to_string()))
+        .with(HitPoints::new(20))
+        .with(Vendor)
+        .with(Wallet {
+            credits: VENDOR_CREDITS,
+        })
+        .build();
+
+    let table = stock_table(world.read_resource::<Depth>().0);
+    let mut rng = rand::thread_rng();
+    let stock: Vec<_> = (0..VENDOR_STOCK)
+        .filter_map(|_| world.read_resource::<LootTables>().roll(table, &mut rng))
+        .collect();
+    for (name, quantity) in stock {
+        if let Some(item) = item_stack(world, name, quantity) {
+            let _ = world
+                .write_storage::<InBackpack>()
+                .insert(item, InBackpack { owner: vendor });
+        }
+    }
+}
+
+/// The loot table vendors roll their stock on, better gear further down
+fn stock_table(depth: i32) -> &'static str {
+    match depth {
+        i32::MIN..=2 => "vendor stock 1",
+        3..=4 => "vendor stock 2",
+        _ => "vendor stock 3",
+    }
+}
+
+fn orc(builder: EntityBuilder) {
+    builder
+        .with(MapRenderable {
+            rep: 'o',
+            color: colors::DESATURATED_GREEN,
+        })
+        .with(Name("orc".to_string()))
+        .with(LootTable {
+            table: "orc",
+            rolls: 1,
+        })
+        .with(HitPoints::new(10))
+        .with(Ai::default())
+        .with(Viewshed::new(SIGHT_RADIUS))
+        .with(Melee {
+            attack: 3,
+            defense: 0,
+            damage_type: DamageType::Kinetic,
+            on_hit: None,
+        })
+        .with(Resistances {
+            thermal: 1.5,
+            ..Default::default()
+        })
+        .build();
+}
+
+fn troll(builder: EntityBuilder) {
+    builder
+        .with(MapRenderable {
+            rep: 'T',
+            color: colors::DARKER_GREEN,
+        })
+        .with(Name("troll".to_string()))
+        .with(LootTable {
+            table: "troll",
+            rolls: 2,
+        })
+        .with(HitPoints::new(16))
+        .with(Ai::default())
+        .with(Viewshed::new(SIGHT_RADIUS))
+        .with(Melee {
+            attack: 4,
+            defense: 1,
+            damage_type: DamageType::Kinetic,
+            // hits hard enough to rattle a mech's pilot
+            on_hit: Some(StatusEffect::new(EffectKind::Stunned, 1)),
+        })
+        .with(Resistances {
+            kinetic: 0.5,
+            thermal: 1.5,
+            ..Default::default()
+        })
+        .build();
+}
+
+fn scout_mech(world: &World, builder: EntityBuilder) {
+    let mech = builder
+        .with(MapRenderable {
+            rep: 'M',
+            color: colors::LIGHT_GREY,
+        })
+        .with(Name("scout mech".to_string()))
+        .with(LootTable {
+            table: "scout mech",
+            rolls: 1,
+        })
+        .with(HitPoints::new(12))
+        .with(Ai::default())
+        .with(Viewshed::new(SIGHT_RADIUS))
+        .with(Melee {
+            attack: 4,
+            defense: 0,
+            damage_type: DamageType::Kinetic,
+            on_hit: None,
+        })
+        .with(
+            MechBody::new(vec![
+                MechPart::new(PartKind::Torso, 6, 12),
+                MechPart::new(PartKind::LeftArm, 2, 6),
+                MechPart::new(PartKind::RightArm, 2, 6),
+                MechPart::new(PartKind::Legs, 4, 8),
+                MechPart::new(PartKind::Sensors, 0, 4),
+            ])
+            .plated(ArmorType::Ablative),
+        )
+        .with(Heat::new(20, 2))
+        .with(RangedWeapon {
+            name: "flamer".to_string(),
+            range: 3,
+            damage: 4,
+            damage_type: DamageType::Thermal,
+            accuracy: 95,
+            ammo_kind: AmmoKind::Fuel,
+            magazine: 4,
+            loaded: 4,
+            reload_turns: 1,
+            heat: 4,
+            mount: PartKind::LeftArm,
+            on_hit: Some(StatusEffect::new(EffectKind::Burning, 3)),
+            warhead: Some(Warhead {
+                shape: AreaShape::Cone { length: 3 },
+                count: 1,
+            }),
+            knockback: 0,
+        })
+        .build();
+
+    ammo(world, AmmoKind::Fuel, 8)
+        .with(InBackpack { owner: mech })
+        .build();
+}
+
+fn rocket_mech(world: &World, builder: EntityBuilder) {
+    let mech = builder
+        .with(MapRenderable {
+            rep: 'R',
+            color: colors::LIGHT_SEPIA,
+        })
+        .with(Name("rocket mech".to_string()))
+        .with(LootTable {
+            table: "rocket mech",
+            rolls: 2,
+        })
+        .with(HitPoints::new(14))
+        .with(Ai::default())
+        .with(Viewshed::new(SIGHT_RADIUS))
+        .with(Melee {
+            attack: 3,
+            defense: 0,
+            damage_type: DamageType::Kinetic,
+            on_hit: None,
+        })
+        .with(
+            MechBody::new(vec![
+                MechPart::new(PartKind::Torso, 8, 14),
+                MechPart::new(PartKind::LeftArm, 4, 6),
+                MechPart::new(PartKind::RightArm, 4, 6),
+                MechPart::new(PartKind::Legs, 4, 10),
+                MechPart::new(PartKind::Sensors, 0, 4),
+            ])
+            .plated(ArmorType::Reflective),
+        )
+        .with(Heat::new(24, 3))
+        .with(RangedWeapon {
+            name: "missile rack".to_string(),
+            range: 10,
+            damage: 5,
+            damage_type: DamageType::Explosive,
+            accuracy: 70,
+            ammo_kind: AmmoKind::Missiles,
+            magazine: 2,
+            loaded: 2,
+            reload_turns: 3,
+            heat: 6,
+            mount: PartKind::Torso,
+            on_hit: None,
+            warhead: Some(Warhead {
+                shape: AreaShape::Burst { radius: 1 },
+                count: 3,
+            }),
+            knockback: 1,
+        })
+        .build();
+
+    ammo(world, AmmoKind::Missiles, 4)
+        .with(InBackpack { owner: mech })
+        .build();
+}
+
+fn bomb_drone(builder: EntityBuilder) {
+    builder
+        .with(MapRenderable {
+            rep: 'd',
+            color: colors::LIGHT_RED,
+        })
+        .with(Name("bomb drone".to_string()))
+        .with(LootTable {
+            table: "bomb drone",
+            rolls: 1,
+        })
+        .with(HitPoints::new(4))
+        .with(Ai::default())
+        .with(Viewshed::new(SIGHT_RADIUS))
+        .with(Melee {
+            attack: 2,
+            defense: 0,
+            damage_type: DamageType::Kinetic,
+            on_hit: None,
+        })
+        .with(Explosive {
+            shape: AreaShape::Burst { radius: 2 },
+            damage: 8,
+            knockback: 1,
+        })
+        .build();
+}
+
+const MAX_ROOM_ITEMS: i32 = 2;
+
+pub fn place_items(world: &mut World, room: Rect) {
+    let num_items = rand::thread_rng().gen_range(0, MAX_ROOM_ITEMS + 1);
+
+    for _ in 0..num_items {
+        let (x, y) = room.rand_inside();
+
+        let roll = rand::thread_rng().gen_range(0, 100);
+        let builder = match roll {
+            0..=14 => repair_kit(world),
+            15..=22 => nanite_paste(world),
+            23..=30 => coolant_flask(world),
+            31..=32 => field_kit(world),
+            33..=34 => scanner(world),
+            35..=38 => survey_chip(world),
+            39..=42 => blink_charge(world),
+            43..=48 => grenade(world),
+            49..=52 => acid_flask(world),
+            53..=58 => emp_charge(world),
+            59..=64 => flare(world),
+            65..=78 => ammo(world, AmmoKind::Shells, 10),
+            79..=84 => ammo(world, AmmoKind::Fuel, 6),
+            85..=89 => ammo(world, AmmoKind::Missiles, 2),
+            90 => vibro_blade(world),
+            91 => flamer(world),
+            92 => hydraulic_ram(world),
+            93..=94 => targeting_computer(world),
+            95..=96 => reactive_plating(world),
+            _ => jump_jets(world),
+        };
+        builder.with(Position { x, y }).build();
+    }
+}
+
+// items that spawn unidentified, and what they can look like
+const CANISTERS: [&str; 3] = ["nanite paste", "coolant flask", "blink charge"];
+const CANISTER_LOOKS: [&str; 6] = [
+    "murky canister",
+    "humming canister",
+    "frosted canister",
+    "dented canister",
+    "leaking canister",
+    "sealed canister",
+];
+const MODULES: [&str; 3] = ["targeting computer", "reactive plating", "jump jets"];
+const MODULE_LOOKS: [&str; 5] = [
+    "scorched module",
+    "ribbed module",
+    "cracked module",
+    "wired module",
+    "blinking module",
+];
+
+/// Deals out appearances to the unidentified items for this run
+pub fn appearances() -> Identification {
+    let mut rng = rand::thread_rng();
+    let mut identification = Identification::default();
+
+    for (kinds, looks) in [
+        (&CANISTERS[..], &CANISTER_LOOKS[..]),
+        (&MODULES[..], &MODULE_LOOKS[..]),
+    ] {
+        let mut looks = looks.to_vec();
+        looks.shuffle(&mut rng);
+        for (kind, look) in kinds.iter().zip(looks) {
+            identification
+                .appearances
+                .insert(kind.to_string(), look.to_string());
+        }
+    }
+
+    identification
+}
+
+/// What each kind of monster leaves behind, item names match `item_stack`
+pub fn loot_tables() -> LootTables {
+    use LootEntry::{Item, Nothing, Table};
+
+    let tables = vec![
+        // shared tables the monsters' own ones roll into
+        (
+            "supplies",
+            vec![
+                (30, Item("repair kit", 1)),
+                (15, Item("nanite paste", 1)),
+                (15, Item("coolant flask", 1)),
+                (10, Item("scanner", 1)),
+                (10, Item("grenade", 1)),
+                (5, Item("acid flask", 1)),
+                (15, Item("flare", 1)),
+            ],
+        ),
+        (
+            "ammo",
+            vec![
+                (50, Item("autocannon shells", 10)),
+                (30, Item("fuel canisters", 6)),
+                (20, Item("missiles", 2)),
+            ],
+        ),
+        (
+            "salvage",
+            vec![
+                (60, Item("scrap", 2)),
+                (15, Item("servo", 1)),
+                (15, Item("actuator", 1)),
+                (10, Item("optics", 1)),
+            ],
+        ),
+        (
+            "modules",
+            vec![
+                (25, Item("vibro blade", 1)),
+                (15, Item("hydraulic ram", 1)),
+                (20, Item("targeting computer", 1)),
+                (20, Item("reactive plating", 1)),
+                (20, Item("jump jets", 1)),
+            ],
+        ),
+        // what vendors sell, by depth
+        (
+            "vendor stock 1",
+            vec![
+                (40, Table("supplies")),
+                (30, Table("ammo")),
+                (10, Item("armor plating", 1)),
+                (10, Item("field kit", 1)),
+                (10, Item("survey chip", 1)),
+            ],
+        ),
+        (
+            "vendor stock 2",
+            vec![
+                (30, Table("supplies")),
+                (25, Table("ammo")),
+                (15, Table("modules")),
+                (10, Item("armor plating", 2)),
+                (10, Item("field kit", 1)),
+                (10, Item("rifled barrel", 1)),
+            ],
+        ),
+        (
+            "vendor stock 3",
+            vec![
+                (20, Table("supplies")),
+                (20, Table("ammo")),
+                (30, Table("modules")),
+                (15, Item("rifled barrel", 1)),
+                (15, Item("depleted core", 1)),
+            ],
+        ),
+        (
+            "orc",
+            vec![(60, Nothing), (25, Table("supplies")), (15, Table("ammo"))],
+        ),
+        (
+            "troll",
+            vec![
+                (50, Nothing),
+                (30, Table("supplies")),
+                (15, Table("ammo")),
+                (5, Table("modules")),
+            ],
+        ),
+        (
+            "scout mech",
+            vec![
+                (30, Nothing),
+                (40, Item("fuel canisters", 6)),
+                (20, Table("salvage")),
+                (10, Table("supplies")),
+            ],
+        ),
+        (
+            "rocket mech",
+            vec![
+                (35, Nothing),
+                (30, Item("missiles", 2)),
+                (20, Table("salvage")),
+                (15, Table("modules")),
+            ],
+        ),
+        (
+            "bomb drone",
+            vec![
+                (70, Nothing),
+                (20, Item("grenade", 1)),
+                (10, Item("scrap", 1)),
+            ],
+        ),
+    ];
+
+    LootTables(tables.into_iter().collect())
+}
+
+/// An item of a kind the player doesn't know yet goes by its appearance
+fn unidentified<'a>(world: &'a World, builder: EntityBuilder<'a>, real: &str) -> EntityBuilder<'a> {
+    let identification = world.read_resource::<Identification>();
+    match identification.appearance(real) {
+        Some(look) => builder.with(Name(look.to_string())).with(Unidentified {
+            real: real.to_string(),
+        }),
+        None => builder,
+    }
+}
+
+/// Everything `item_stack` can make, kept in step with its `match`
+pub const ITEM_NAMES: &[&str] = &[
+    "autocannon shells",
+    "fuel canisters",
+    "missiles",
+    "repair kit",
+    "nanite paste",
+    "coolant flask",
+    "field kit",
+    "survey chip",
+    "blink charge",
+    "grenade",
+    "EMP charge",
+    "acid flask",
+    "flare",
+    "scanner",
+    "scrap",
+    "servo",
+    "actuator",
+    "optics",
+    "armor plating",
+    "rifled barrel",
+    "depleted core",
+    "autocannon",
+    "flamer",
+    "vibro blade",
+    "hydraulic ram",
+    "targeting computer",
+    "reactive plating",
+    "jump jets",
+];
+
+/// An item made from its name, for splitting stacks, salvage and crafting.
+/// Stackable items come `quantity` to the stack, anything else just once.
+pub fn item_stack(world: &mut World, name: &str, quantity: i32) -> Option<Entity> {
+    let builder = match name {
+        "autocannon shells" => ammo(world, AmmoKind::Shells, quantity),
+        "fuel canisters" => ammo(world, AmmoKind::Fuel, quantity),
+        "missiles" => ammo(world, AmmoKind::Missiles, quantity),
+        "repair kit" => repair_kit(world),
+        "nanite paste" => nanite_paste(world),
+        "coolant flask" => coolant_flask(world),
+        "field kit" => field_kit(world),
+        "survey chip" => survey_chip(world),
+        "blink charge" => blink_charge(world),
+        "grenade" => grenade(world),
+        "EMP charge" => emp_charge(world),
+        "acid flask" => acid_flask(world),
+        "flare" => flare(world),
+        "scanner" => scanner(world),
+        "scrap" => salvage(world, "scrap", 2, 20),
+        "servo" => salvage(world, "servo", 10, 5),
+        "actuator" => salvage(world, "actuator", 10, 5),
+        "optics" => salvage(world, "optics", 12, 5),
+        "armor plating" => armor_plating(world),
+        "rifled barrel" => rifled_barrel(world),
+        "depleted core" => depleted_core(world),
+        "autocannon" => autocannon(world),
+        "flamer" => flamer(world),
+        "vibro blade" => vibro_blade(world),
+        "hydraulic ram" => hydraulic_ram(world),
+        "targeting computer" => targeting_computer(world),
+        "reactive plating" => reactive_plating(world),
+        "jump jets" => jump_jets(world),
+        _ => return None,
+    };
+    let item = builder.build();
+
+    if let Some(stack) = world.write_storage::<Stackable>().get_mut(item) {
+        stack.quantity = quantity;
+    }
+    Some(item)
+}
+
+// items are left without a place, to be put on the map or in a backpack
+
+fn ammo(world: &World, kind: AmmoKind, rounds: i32) -> EntityBuilder<'_> {
+    // weight and value are per round, the stack limit is in rounds too
+    let (name, weight, value, limit) = match kind {
+        AmmoKind::Shells => ("autocannon shells", 1, 1, 60),
+        AmmoKind::Fuel => ("fuel canisters", 1, 2, 24),
+        AmmoKind::Missiles => ("missiles", 3, 8, 12),
+    };
+
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '=',
+            color: colors::LIGHTER_AMBER,
+        })
+        .with(Name(name.to_string()))
+        .with(Item { weight, value })
+        .with(Ammo { kind })
+        .with(Stackable {
+            quantity: rounds,
+            limit,
+        })
+}
+
+fn repair_kit(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '+',
+            color: colors::LIGHT_SKY,
+        })
+        .with(Name("repair kit".to_string()))
+        .with(Item {
+            weight: 3,
+            value: 20,
+        })
+        .with(Stackable::new(5))
+        .with(Consumable)
+        .with(ProvidesRepair { armor: 8 })
+}
+
+fn nanite_paste(world: &World) -> EntityBuilder<'_> {
+    let builder = world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '!',
+            color: colors::LIGHT_GREEN,
+        })
+        .with(Name("nanite paste".to_string()))
+        .with(Item {
+            weight: 1,
+            value: 25,
+        })
+        .with(Stackable::new(5))
+        .with(Consumable)
+        .with(ProvidesHealing { amount: 8 });
+    unidentified(world, builder, "nanite paste")
+}
+
+fn coolant_flask(world: &World) -> EntityBuilder<'_> {
+    let builder = world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '!',
+            color: colors::LIGHT_BLUE,
+        })
+        .with(Name("coolant flask".to_string()))
+        .with(Item {
+            weight: 2,
+            value: 15,
+        })
+        .with(Stackable::new(5))
+        .with(Consumable)
+        .with(ProvidesCooling { heat: 20 });
+    unidentified(world, builder, "coolant flask")
+}
+
+// a bit of everything, in one box
+fn field_kit(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '+',
+            color: colors::LIGHT_GREEN,
+        })
+        .with(Name("field kit".to_string()))
+        .with(Item {
+            weight: 4,
+            value: 60,
+        })
+        .with(Stackable::new(3))
+        .with(Consumable)
+        .with(ProvidesHealing { amount: 4 })
+        .with(ProvidesRepair { armor: 4 })
+        .with(ProvidesCooling { heat: 10 })
+}
+
+fn survey_chip(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '?',
+            color: colors::LIGHT_YELLOW,
+        })
+        .with(Name("survey chip".to_string()))
+        .with(Item {
+            weight: 0,
+            value: 30,
+        })
+        .with(Stackable::new(3))
+        .with(Consumable)
+        .with(RevealsMap)
+}
+
+fn blink_charge(world: &World) -> EntityBuilder<'_> {
+    let builder = world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '?',
+            color: colors::LIGHT_MAGENTA,
+        })
+        .with(Name("blink charge".to_string()))
+        .with(Item {
+            weight: 1,
+            value: 35,
+        })
+        .with(Stackable::new(3))
+        .with(Consumable)
+        .with(Teleports);
+    unidentified(world, builder, "blink charge")
+}
+
+fn grenade(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '*',
+            color: colors::LIGHT_RED,
+        })
+        .with(Name("grenade".to_string()))
+        .with(Item {
+            weight: 1,
+            value: 15,
+        })
+        .with(Stackable::new(6))
+        .with(Consumable)
+        .with(Targeted { range: 6 })
+        .with(AreaOfEffect {
+            shape: AreaShape::Burst { radius: 2 },
+        })
+        .with(InflictsDamage {
+            damage: 8,
+            damage_type: DamageType::Explosive,
+        })
+}
+
+fn emp_charge(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '*',
+            color: colors::LIGHT_BLUE,
+        })
+        .with(Name("EMP charge".to_string()))
+        .with(Item {
+            weight: 2,
+            value: 25,
+        })
+        .with(Stackable::new(4))
+        .with(Consumable)
+        .with(Targeted { range: 5 })
+        .with(AreaOfEffect {
+            shape: AreaShape::Burst { radius: 2 },
+        })
+        .with(InflictsDamage {
+            damage: 2,
+            damage_type: DamageType::Electric,
+        })
+        .with(InflictsEffect {
+            effect: StatusEffect::new(EffectKind::Emp, 4),
+        })
+}
+
+fn acid_flask(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '*',
+            color: colors::LIME,
+        })
+        .with(Name("acid flask".to_string()))
+        .with(Item {
+            weight: 1,
+            value: 20,
+        })
+        .with(Stackable::new(6))
+        .with(Consumable)
+        .with(Targeted { range: 6 })
+        .with(AreaOfEffect {
+            shape: AreaShape::Burst { radius: 1 },
+        })
+        .with(InflictsDamage {
+            damage: 3,
+            damage_type: DamageType::Corrosive,
+        })
+        .with(InflictsEffect {
+            effect: StatusEffect::new(EffectKind::Corroding, 4),
+        })
+}
+
+fn flare(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '*',
+            color: colors::LIGHT_YELLOW,
+        })
+        .with(Name("flare".to_string()))
+        .with(Item {
+            weight: 1,
+            value: 8,
+        })
+        .with(Stackable::new(6))
+        .with(Consumable)
+        .with(Targeted { range: 10 })
+        .with(AreaOfEffect {
+            shape: AreaShape::Burst { radius: 4 },
+        })
+        .with(Illuminates { turns: 12 })
+}
+
+fn autocannon(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '}',
+            color: colors::LIGHT_GREY,
+        })
+        .with(Name("autocannon".to_string()))
+        .with(Item {
+            weight: 10,
+            value: 150,
+        })
+        .with(Equippable {
+            mounts: vec![Hardpoint::LeftArm, Hardpoint::RightArm],
+            size: MountSize::Heavy,
+        })
+        .with(Armament)
+        .with(RangedWeapon {
+            name: "autocannon".to_string(),
+            range: 8,
+            damage: 6,
+            damage_type: DamageType::Kinetic,
+            accuracy: 85,
+            ammo_kind: AmmoKind::Shells,
+            magazine: 20,
+            loaded: 20,
+            reload_turns: 2,
+            heat: 3,
+            mount: PartKind::RightArm,
+            on_hit: None,
+            warhead: None,
+            knockback: 1,
+        })
+}
+
+fn flamer(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '}',
+            color: colors::LIGHT_ORANGE,
+        })
+        .with(Name("flamer".to_string()))
+        .with(Item {
+            weight: 6,
+            value: 110,
+        })
+        .with(Equippable {
+            mounts: vec![Hardpoint::LeftArm, Hardpoint::RightArm],
+            size: MountSize::Medium,
+        })
+        .with(Armament)
+        .with(RangedWeapon {
+            name: "flamer".to_string(),
+            range: 3,
+            damage: 4,
+            damage_type: DamageType::Thermal,
+            accuracy: 95,
+            ammo_kind: AmmoKind::Fuel,
+            magazine: 4,
+            loaded: 0,
+            reload_turns: 1,
+            heat: 4,
+            mount: PartKind::LeftArm,
+            on_hit: Some(StatusEffect::new(EffectKind::Burning, 3)),
+            warhead: Some(Warhead {
+                shape: AreaShape::Cone { length: 3 },
+                count: 1,
+            }),
+            knockback: 0,
+        })
+}
+
+fn vibro_blade(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '/',
+            color: colors::LIGHT_CYAN,
+        })
+        .with(Name("vibro blade".to_string()))
+        .with(Item {
+            weight: 4,
+            value: 80,
+        })
+        .with(Equippable {
+            mounts: vec![Hardpoint::LeftArm, Hardpoint::RightArm],
+            size: MountSize::Medium,
+        })
+        .with(MeleeBonus { attack: 3 })
+}
+
+fn hydraulic_ram(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '/',
+            color: colors::LIGHT_GREY,
+        })
+        .with(Name("hydraulic ram".to_string()))
+        .with(Item {
+            weight: 9,
+            value: 120,
+        })
+        .with(Equippable {
+            mounts: vec![Hardpoint::LeftArm, Hardpoint::RightArm],
+            size: MountSize::Heavy,
+        })
+        .with(MeleeBonus { attack: 6 })
+}
+
+fn targeting_computer(world: &World) -> EntityBuilder<'_> {
+    let builder = world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '[',
+            color: colors::LIGHT_GREEN,
+        })
+        .with(Name("targeting computer".to_string()))
+        .with(Item {
+            weight: 2,
+            value: 100,
+        })
+        .with(Equippable {
+            mounts: vec![Hardpoint::Shoulder],
+            size: MountSize::Light,
+        })
+        .with(AccuracyBonus { accuracy: 10 });
+    unidentified(world, builder, "targeting computer")
+}
+
+fn reactive_plating(world: &World) -> EntityBuilder<'_> {
+    let builder = world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '[',
+            color: colors::LIGHT_SKY,
+        })
+        .with(Name("reactive plating".to_string()))
+        .with(Item {
+            weight: 10,
+            value: 110,
+        })
+        .with(Equippable {
+            mounts: vec![Hardpoint::Torso],
+            size: MountSize::Heavy,
+        })
+        .with(DefenseBonus { defense: 2 });
+    unidentified(world, builder, "reactive plating")
+}
+
+fn jump_jets(world: &World) -> EntityBuilder<'_> {
+    let builder = world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '[',
+            color: colors::LIGHT_ORANGE,
+        })
+        .with(Name("jump jets".to_string()))
+        .with(Item {
+            weight: 6,
+            value: 90,
+        })
+        .with(Equippable {
+            mounts: vec![Hardpoint::Legs],
+            size: MountSize::Medium,
+        })
+        .with(MobilityBonus { sprint_heat: 3 });
+    unidentified(world, builder, "jump jets")
+}
+
+fn scanner(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '?',
+            color: colors::LIGHT_GREEN,
+        })
+        .with(Name("scanner".to_string()))
+        .with(Item {
+            weight: 1,
+            value: 40,
+        })
+        .with(Stackable::new(3))
+        .with(Consumable)
+        .with(Identifies)
+}
+
+/// Parts and scrap pulled out of wrecks, good for crafting or selling
+fn salvage<'a>(world: &'a World, name: &str, value: i32, limit: i32) -> EntityBuilder<'a> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '&',
+            color: colors::GREY,
+        })
+        .with(Name(name.to_string()))
+        .with(Item { weight: 1, value })
+        .with(Stackable::new(limit))
+}
+
+fn armor_plating(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '+',
+            color: colors::SKY,
+        })
+        .with(Name("armor plating".to_string()))
+        .with(Item {
+            weight: 5,
+            value: 30,
+        })
+        .with(Stackable::new(3))
+        .with(Consumable)
+        .with(ProvidesRepair { armor: 14 })
+}
+
+fn rifled_barrel(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '(',
+            color: colors::LIGHT_AMBER,
+        })
+        .with(Name("rifled barrel".to_string()))
+        .with(Item {
+            weight: 3,
+            value: 70,
+        })
+        .with(Consumable)
+        .with(UpgradesWeapon {
+            damage: 0,
+            accuracy: 5,
+        })
+}
+
+fn depleted_core(world: &World) -> EntityBuilder<'_> {
+    world
+        .create_entity_unchecked()
+        .with(MapRenderable {
+            rep: '(',
+            color: colors::LIGHT_PURPLE,
+        })
+        .with(Name("depleted core".to_string()))
+        .with(Item {
+            weight: 2,
+            value: 70,
+        })
+        .with(Consumable)
+        .with(UpgradesWeapon {
+            damage: 1,
+            accuracy: 0,
+        })
+}
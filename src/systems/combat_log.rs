@@ -0,0 +1,42 @@
+use specs::{Read, System, Write};
+
+use crate::resources::{CombatLog, GameMode, InputMapping, VirtualKey};
+
+#[derive(Default)]
+pub struct CombatLogView;
+
+impl<'a> System<'a> for CombatLogView {
+    type SystemData = (
+        Read<'a, InputMapping>,
+        Read<'a, CombatLog>,
+        Write<'a, GameMode>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (im, combat_log, mut mode) = data;
+
+        match (*mode, &im.key) {
+            (GameMode::Normal, Some(VirtualKey::CombatLog)) => {
+                *mode = GameMode::CombatLog { scroll: 0 };
+            }
+            (GameMode::CombatLog { scroll }, Some(key)) => match key {
+                VirtualKey::MoveUp => {
+                    let oldest = combat_log.records.len().saturating_sub(1);
+                    *mode = GameMode::CombatLog {
+                        scroll: (scroll + 1).min(oldest),
+                    };
+                }
+                VirtualKey::MoveDown => {
+                    *mode = GameMode::CombatLog {
+                        scroll: scroll.saturating_sub(1),
+                    };
+                }
+                VirtualKey::CombatLog | VirtualKey::Cancel => {
+                    *mode = GameMode::Normal;
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::ops::Deref;
+
+use specs::storage::MaskedStorage;
+use specs::{
+    join::Join, Entities, Entity, Read, ReadStorage, Storage, System, Write, WriteStorage,
+};
+
+use super::inventory::letter_index;
+use super::player_control::busy;
+use crate::components::{
+    Heat, HitPoints, InBackpack, Name, Player, Reloading, Stackable, StatusEffects, WantsToCraft,
+};
+use crate::resources::{GameMode, InputMapping, MesLogs, PlayerTurn, Recipe, Recipes, VirtualKey};
+
+#[derive(Default)]
+pub struct CraftingMenu;
+
+impl<'a> System<'a> for CraftingMenu {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, HitPoints>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Stackable>,
+        ReadStorage<'a, Heat>,
+        ReadStorage<'a, StatusEffects>,
+        ReadStorage<'a, Reloading>,
+        WriteStorage<'a, WantsToCraft>,
+        Read<'a, Recipes>,
+        Read<'a, InputMapping>,
+        Write<'a, GameMode>,
+        Write<'a, PlayerTurn>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player,
+            hp,
+            names,
+            backpack,
+            stacks,
+            heat,
+            effects,
+            reloading,
+            mut wants_craft,
+            recipes,
+            im,
+            mut mode,
+            mut turn,
+            mut logs,
+        ) = data;
+
+        let player_entity = match (&entities, &player).join().next() {
+            Some((e, _)) => e,
+            None => return,
+        };
+
+        let letter = match (*mode, &im.key) {
+            (GameMode::Normal, Some(VirtualKey::Crafting)) => {
+                *mode = GameMode::Crafting;
+                return;
+            }
+            (GameMode::Crafting, Some(VirtualKey::Cancel)) => {
+                *mode = GameMode::Normal;
+                return;
+            }
+            (GameMode::Crafting, Some(VirtualKey::Letter(letter))) => *letter,
+            _ => return,
+        };
+
+        let (idx, recipe) =
+            match letter_index(letter).and_then(|idx| Some((idx, recipes.0.get(idx)?))) {
+                Some(found) => found,
+                None => return,
+            };
+
+        if let Some((quantity, name)) = missing(recipe, &backpack, &stacks, &names, player_entity) {
+            logs.add_message(format!("You need {} more {}.", quantity, name));
+            return;
+        }
+
+        *mode = GameMode::Normal;
+
+        if !hp.get(player_entity).is_some_and(HitPoints::alive) {
+            return;
+        }
+        if let Some(reason) = busy(player_entity, &names, &heat, &effects, &reloading) {
+            logs.add_message(reason);
+            turn.0 = true;
+            return;
+        }
+
+        let _ = wants_craft.insert(player_entity, WantsToCraft { recipe: idx });
+        turn.0 = true;
+    }
+}
+
+/// How many of `name` `owner` carries, counting whole stacks
+pub fn carried<B, S, N>(
+    backpack: &Storage<InBackpack, B>,
+    stacks: &Storage<Stackable, S>,
+    names: &Storage<Name, N>,
+    owner: Entity,
+    name: &str,
+) -> i32
+where
+    B: Deref<Target = MaskedStorage<InBackpack>>,
+    S: Deref<Target = MaskedStorage<Stackable>>,
+    N: Deref<Target = MaskedStorage<Name>>,
+{
+    (backpack, names, stacks.maybe())
+        .join()
+        .filter(|(b, n, _)| b.owner == owner && n.0 == name)
+        .map(|(_, _, s)| s.map_or(1, |s| s.quantity))
+        .sum()
+}
+
+/// The first ingredient `owner` is short of for `recipe`, and by how much
+pub fn missing<'r, B, S, N>(
+    recipe: &'r Recipe,
+    backpack: &Storage<InBackpack, B>,
+    stacks: &Storage<Stackable, S>,
+    names: &Storage<Name, N>,
+    owner: Entity,
+) -> Option<(i32, &'r str)>
+where
+    B: Deref<Target = MaskedStorage<InBackpack>>,
+    S: Deref<Target = MaskedStorage<Stackable>>,
+    N: Deref<Target = MaskedStorage<Name>>,
+{
+    recipe.inputs.iter().find_map(|(quantity, name)| {
+        let short = quantity - carried(backpack, stacks, names, owner, name);
+        (short > 0).then_some((short, name.as_str()))
+    })
+}
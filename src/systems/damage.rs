@@ -0,0 +1,127 @@
+use specs::{join::Join, Entities, ReadStorage, System, Write, WriteStorage};
+
+use crate::components::{HitPoints, MechBody, Name, Resistances, StatusEffects, SufferDamage};
+use crate::resources::{AttackRecord, CombatLog, HitOutcome, MesLogs};
+
+#[derive(Default)]
+pub struct Damage;
+
+impl<'a> System<'a> for Damage {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, SufferDamage>,
+        WriteStorage<'a, HitPoints>,
+        WriteStorage<'a, MechBody>,
+        WriteStorage<'a, StatusEffects>,
+        ReadStorage<'a, Resistances>,
+        ReadStorage<'a, Name>,
+        Write<'a, MesLogs>,
+        Write<'a, CombatLog>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut suffer,
+            mut hp,
+            mut bodies,
+            mut effects,
+            resistances,
+            names,
+            mut logs,
+            mut combat_log,
+        ) = data;
+
+        for (victim, suffering, hp) in (&entities, &suffer, &mut hp).join() {
+            for hit in &suffering.hits {
+                let multiplier = resistances
+                    .get(victim)
+                    .map_or(1.0, |r| r.multiplier(hit.damage_type));
+                let damage = (hit.amount as f32 * multiplier).round() as i32;
+
+                let outcome = if multiplier < 1.0 {
+                    ", resisted"
+                } else if multiplier > 1.0 {
+                    ", vulnerable"
+                } else {
+                    ""
+                };
+
+                let victim_name = Name::of(&names, victim);
+
+                let (target, location, dealt, absorbed, breached, destroyed) =
+                    match bodies.get_mut(victim) {
+                        Some(body) => {
+                            let part_hit = body.take_hit(damage, hit.damage_type);
+
+                            // a mech stands as long as its torso does
+                            hp.current = body.torso_structure();
+                            (
+                                format!("{}'s {}", victim_name, part_hit.part.name()),
+                                Some(part_hit.part.name()),
+                                part_hit.damage,
+                                part_hit.absorbed,
+                                part_hit.breached,
+                                part_hit.destroyed,
+                            )
+                        }
+                        None => {
+                            if damage > 0 {
+                                hp.current -= damage;
+                            }
+                            (victim_name.clone(), None, damage, 0, false, false)
+                        }
+                    };
+
+                combat_log.record(AttackRecord {
+                    attacker: Name::of(&names, hit.source),
+                    target: victim_name.clone(),
+                    attack: hit.attack.clone(),
+                    outcome: Some(HitOutcome {
+                        base: hit.amount,
+                        damage_type: hit.damage_type.name(),
+                        multiplier,
+                        location,
+                        absorbed,
+                        damage: dealt,
+                    }),
+                });
+
+                let outcome = if absorbed > 0 {
+                    format!("{}, {} absorbed by armor", outcome, absorbed)
+                } else {
+                    outcome.to_string()
+                };
+
+                // lingering effects hurt their victim without anybody attacking
+                let report = if hit.source == victim {
+                    format!("{} takes", target)
+                } else {
+                    format!("{} attacks {} for", Name::of(&names, hit.source), target)
+                };
+                logs.add_message(format!(
+                    "{} {} {} damage{}.",
+                    report,
+                    dealt,
+                    hit.damage_type.name(),
+                    outcome
+                ));
+                if breached {
+                    logs.add_message(format!("{} armor is stripped away!", target));
+                }
+                if destroyed {
+                    logs.add_message(format!("{} is destroyed!", target));
+                }
+
+                if let Some(effect) = hit.effect {
+                    if dealt > 0 && hp.alive() {
+                        logs.add_message(format!("{} is {}!", victim_name, effect.kind.name()));
+                        StatusEffects::inflict(&mut effects, victim, effect);
+                    }
+                }
+            }
+        }
+
+        suffer.clear();
+    }
+}
@@ -0,0 +1,164 @@
+use specs::{
+    join::Join, Entities, LazyUpdate, Read, ReadExpect, ReadStorage, System, Write, WriteStorage,
+};
+
+use tcod::colors;
+
+use crate::components::{
+    Attack, Blast, DamageType, Dead, Explosive, HitPoints, LootTable, MapRenderable, MechBody,
+    Melee, Name, Player, Position, Salvageable,
+};
+use crate::map::Map;
+use crate::resources::{Identification, LootTables, MesLogs};
+use crate::spawner;
+
+#[derive(Default)]
+pub struct Death;
+
+impl<'a> System<'a> for Death {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, HitPoints>,
+        WriteStorage<'a, Dead>,
+        WriteStorage<'a, Melee>,
+        WriteStorage<'a, MapRenderable>,
+        WriteStorage<'a, Name>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Explosive>,
+        ReadStorage<'a, MechBody>,
+        WriteStorage<'a, Salvageable>,
+        WriteStorage<'a, Blast>,
+        ReadStorage<'a, LootTable>,
+        ReadExpect<'a, Map>,
+        Read<'a, LootTables>,
+        Read<'a, Identification>,
+        Read<'a, LazyUpdate>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut hp,
+            mut dead,
+            mut melee,
+            mut mapr,
+            mut names,
+            player,
+            pos,
+            explosive,
+            bodies,
+            mut salvage,
+            mut blasts,
+            loot,
+            map,
+            loot_tables,
+            identification,
+            lazy,
+            mut logs,
+        ) = data;
+
+        let dying: Vec<_> = (&entities, &hp, !&dead)
+            .join()
+            .filter(|(_, hp, _)| !hp.alive())
+            .map(|(e, _, _)| e)
+            .collect();
+
+        for e in dying {
+            let _ = dead.insert(e, Dead);
+
+            // for added effect, transform the dead into a corpse!
+            if let Some(r) = mapr.get_mut(e) {
+                r.rep = '%';
+                r.color = colors::DARK_RED;
+            }
+
+            if player.get(e).is_some() {
+                // the game ended!
+                logs.add_message("You died!");
+                continue;
+            }
+
+            // some things don't go down quietly
+            if let (Some(explosive), Some(p)) = (explosive.get(e), pos.get(e)) {
+                logs.add_message(format!("{} explodes!", Name::of(&names, e)));
+                let _ = blasts.insert(
+                    entities.create(),
+                    Blast {
+                        center: (p.x, p.y),
+                        toward: (p.x, p.y),
+                        shape: explosive.shape,
+                        damage: explosive.damage,
+                        damage_type: DamageType::Explosive,
+                        effect: None,
+                        knockback: explosive.knockback,
+                        source: e,
+                        attack: Attack::new("explosion"),
+                    },
+                );
+            }
+
+            // a monster corpse doesn't block, can't be attacked and doesn't move
+            let name = Name::of(&names, e);
+            logs.add_message(format!("{} is dead!", name));
+            hp.remove(e);
+            melee.remove(e);
+
+            // dead mechs leave a wreck to strip for parts
+            match bodies.get(e) {
+                Some(body) => {
+                    let _ = salvage.insert(e, Salvageable::from_body(body));
+                    let _ = names.insert(e, Name(format!("wreck of {}", name)));
+                    if let Some(r) = mapr.get_mut(e) {
+                        r.color = colors::DARK_GREY;
+                    }
+                }
+                None => {
+                    let _ = names.insert(e, Name(format!("remains of {}", name)));
+                }
+            }
+
+            if let (Some(loot), Some(p)) = (loot.get(e), pos.get(e)) {
+                let mut rng = rand::thread_rng();
+                let drops: Vec<_> = (0..loot.rolls)
+                    .filter_map(|_| loot_tables.roll(loot.table, &mut rng))
+                    .collect();
+                if drops.is_empty() {
+                    continue;
+                }
+
+                let dropped: Vec<_> = drops
+                    .iter()
+                    .map(|&(item, _)| identification.appearance(item).unwrap_or(item))
+                    .collect();
+                logs.add_message(format!("{} drops {}.", name, dropped.join(", ")));
+
+                let tiles = drop_tiles(&map, (p.x, p.y));
+                lazy.exec_mut(move |world| {
+                    for (&(item, quantity), &(x, y)) in drops.iter().zip(tiles.iter().cycle()) {
+                        if let Some(item) = spawner::item_stack(world, item, quantity) {
+                            let _ = world
+                                .write_storage::<Position>()
+                                .insert(item, Position { x, y });
+                        }
+                    }
+                });
+            }
+        }
+    }
+}
+
+// the tile the monster died on first, then the open tiles around it
+fn drop_tiles(map: &Map, (x, y): (i32, i32)) -> Vec<(i32, i32)> {
+    let mut tiles = vec![(x, y)];
+    for dx in -1..=1 {
+        for dy in -1..=1 {
+            let (nx, ny) = (x + dx, y + dy);
+            if (dx, dy) != (0, 0) && map.in_bounds(nx, ny) && !map.blocked(nx, ny) {
+                tiles.push((nx, ny));
+            }
+        }
+    }
+    tiles
+}
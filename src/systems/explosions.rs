@@ -0,0 +1,101 @@
+use specs::{join::Join, Entities, ReadExpect, ReadStorage, System, Write, WriteStorage};
+
+use crate::components::{
+    Blast, DefenseBonus, Equipped, Hit, HitPoints, Knockback, MechBody, Position, SufferDamage,
+};
+use crate::consts::FLASH_FRAMES;
+use crate::map::{direction, distance, Map};
+use crate::resources::Flashes;
+
+#[derive(Default)]
+pub struct Explosions;
+
+impl<'a> System<'a> for Explosions {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Blast>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, HitPoints>,
+        ReadStorage<'a, MechBody>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, DefenseBonus>,
+        WriteStorage<'a, SufferDamage>,
+        WriteStorage<'a, Knockback>,
+        ReadExpect<'a, Map>,
+        Write<'a, Flashes>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut blasts,
+            pos,
+            hp,
+            bodies,
+            equipped,
+            defense_bonus,
+            mut suffer,
+            mut knockback,
+            map,
+            mut flashes,
+        ) = data;
+
+        for (blast_entity, blast) in (&entities, &blasts).join() {
+            let area = map.area(blast.shape, blast.center, blast.toward);
+
+            for (victim, p, _) in (&entities, &pos, &hp)
+                .join()
+                .filter(|(_, _, hp)| hp.alive())
+            {
+                if !area.contains(&(p.x, p.y)) {
+                    continue;
+                }
+
+                // full damage at the center, fading out towards the edge
+                let reach = blast.shape.reach() as f32 + 1.0;
+                let falloff = 1.0 - distance(blast.center, (p.x, p.y)) / reach;
+                let faded = (blast.damage as f32 * falloff).round() as i32;
+                let defense =
+                    Equipped::bonus(&equipped, &defense_bonus, &bodies, victim, |b| b.defense);
+                let amount = faded - defense;
+                if amount > 0 {
+                    SufferDamage::add_hit(
+                        &mut suffer,
+                        victim,
+                        Hit {
+                            source: blast.source,
+                            amount,
+                            damage_type: blast.damage_type,
+                            effect: blast.effect,
+                            attack: blast
+                                .attack
+                                .clone()
+                                .modifier("falloff", faded - blast.damage)
+                                .modifier("defense", -defense),
+                        },
+                    );
+                }
+
+                // everything but what sits right at the center gets thrown outwards
+                let (dx, dy) = direction(blast.center, (p.x, p.y));
+                if blast.knockback > 0 && (dx, dy) != (0, 0) {
+                    let _ = knockback.insert(
+                        victim,
+                        Knockback {
+                            dx,
+                            dy,
+                            force: blast.knockback,
+                        },
+                    );
+                }
+            }
+
+            flashes.tiles.extend(area);
+            flashes.frames = FLASH_FRAMES;
+
+            let _ = entities.delete(blast_entity);
+        }
+
+        blasts.clear();
+    }
+}
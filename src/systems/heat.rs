@@ -0,0 +1,76 @@
+use rand::Rng;
+use specs::{join::Join, Entities, Entity, Read, ReadStorage, System, Write, WriteStorage};
+
+use crate::components::{Heat, HeatLevel, HitPoints, MechBody, Name};
+use crate::consts::{OVERHEAT_DAMAGE, SHUTDOWN_TURNS};
+use crate::resources::{MesLogs, PlayerTurn};
+
+#[derive(Default)]
+pub struct HeatManagement;
+
+impl<'a> System<'a> for HeatManagement {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Heat>,
+        WriteStorage<'a, MechBody>,
+        WriteStorage<'a, HitPoints>,
+        ReadStorage<'a, Name>,
+        Read<'a, PlayerTurn>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut heat, mut bodies, mut hp, names, turn, mut logs) = data;
+
+        if !turn.0 {
+            return;
+        }
+
+        for (e, heat, hp) in (&entities, &mut heat, &mut hp).join() {
+            if !hp.alive() {
+                continue;
+            }
+
+            // heat sinks work twice as well with the reactor off
+            if heat.shut_down() {
+                heat.current -= heat.dissipation * 2;
+                heat.shutdown -= 1;
+                if !heat.shut_down() {
+                    logs.add_message(format!("{} powers back up.", Name::of(&names, e)));
+                }
+            } else {
+                heat.current -= heat.dissipation;
+            }
+            heat.current = heat.current.max(0);
+
+            match heat.level() {
+                HeatLevel::Overheated => {
+                    // cooking from the inside
+                    logs.add_message(format!(
+                        "{} overheats and takes internal damage!",
+                        Name::of(&names, e)
+                    ));
+                    match bodies.get_mut(e) {
+                        Some(body) => {
+                            body.internal_damage(OVERHEAT_DAMAGE);
+                            hp.current = body.torso_structure();
+                        }
+                        None => hp.current -= OVERHEAT_DAMAGE,
+                    }
+                    shut_down(e, heat, &names, &mut logs);
+                }
+                HeatLevel::Critical if rand::thread_rng().gen_range(0, 100) < 25 => {
+                    shut_down(e, heat, &names, &mut logs);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn shut_down(e: Entity, heat: &mut Heat, names: &ReadStorage<Name>, logs: &mut MesLogs) {
+    if !heat.shut_down() {
+        logs.add_message(format!("{} shuts down from the heat!", Name::of(names, e)));
+        heat.shutdown = SHUTDOWN_TURNS;
+    }
+}
@@ -0,0 +1,41 @@
+use specs::{join::Join, Entities, System, Write, WriteStorage};
+
+use crate::components::{Name, Unidentified};
+use crate::resources::{Identification, MesLogs};
+
+#[derive(Default)]
+pub struct ItemIdentification;
+
+impl<'a> System<'a> for ItemIdentification {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Unidentified>,
+        WriteStorage<'a, Name>,
+        Write<'a, Identification>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut unidentified, mut names, mut identification, mut logs) = data;
+
+        if identification.fresh.is_empty() {
+            return;
+        }
+
+        for (appearance, real) in identification.fresh.drain(..) {
+            logs.add_message(format!("The {} is identified as {}.", appearance, real));
+        }
+
+        // every item of a known kind goes by its real name from now on
+        let mut revealed = vec![];
+        for (e, unknown, name) in (&entities, &unidentified, &mut names).join() {
+            if identification.known.contains(&unknown.real) {
+                name.0 = unknown.real.clone();
+                revealed.push(e);
+            }
+        }
+        for e in revealed {
+            unidentified.remove(e);
+        }
+    }
+}
@@ -1,19 +1,31 @@
-use specs::{System, Write};
+use specs::{Read, System, Write};
 
 use tcod::input::{self, Event, Key};
 
-use crate::resources::{InputMapping, VirtualKey};
+use crate::resources::{GameMode, InputMapping, VirtualKey};
 
 #[derive(Default)]
 pub struct Input;
 
 impl<'a> System<'a> for Input {
-    type SystemData = Write<'a, InputMapping>;
+    type SystemData = (Write<'a, InputMapping>, Read<'a, GameMode>);
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut im, mode) = data;
 
-    fn run(&mut self, mut im: Self::SystemData) {
         // fetch input, this is done here to avoid being parallel executed by Specs
         use tcod::input::KeyCode::*;
 
+        let normal = *mode == GameMode::Normal;
+        let menu = matches!(
+            *mode,
+            GameMode::Inventory { .. }
+                | GameMode::DropAmount { .. }
+                | GameMode::Loadout
+                | GameMode::Crafting
+                | GameMode::Shop { .. }
+        );
+
         match input::check_for_event(input::MOUSE | input::KEY_PRESS) {
             Some((_, Event::Mouse(m))) => {
                 *im = InputMapping {
@@ -23,11 +35,162 @@ impl<'a> System<'a> for Input {
             }
             Some((_, Event::Key(k))) => {
                 let vkey = match k {
+                    // menus pick their entries by letter
+                    Key {
+                        code: Char,
+                        printable,
+                        ..
+                    } if menu => VirtualKey::Letter(printable),
+                    // and amounts by number, from either row of digits
+                    Key { code: Number0, .. } | Key { code: NumPad0, .. } if menu => {
+                        VirtualKey::Digit(0)
+                    }
+                    Key { code: Number1, .. } | Key { code: NumPad1, .. } if menu => {
+                        VirtualKey::Digit(1)
+                    }
+                    Key { code: Number2, .. } | Key { code: NumPad2, .. } if menu => {
+                        VirtualKey::Digit(2)
+                    }
+                    Key { code: Number3, .. } | Key { code: NumPad3, .. } if menu => {
+                        VirtualKey::Digit(3)
+                    }
+                    Key { code: Number4, .. } | Key { code: NumPad4, .. } if menu => {
+                        VirtualKey::Digit(4)
+                    }
+                    Key { code: Number5, .. } | Key { code: NumPad5, .. } if menu => {
+                        VirtualKey::Digit(5)
+                    }
+                    Key { code: Number6, .. } | Key { code: NumPad6, .. } if menu => {
+                        VirtualKey::Digit(6)
+                    }
+                    Key { code: Number7, .. } | Key { code: NumPad7, .. } if menu => {
+                        VirtualKey::Digit(7)
+                    }
+                    Key { code: Number8, .. } | Key { code: NumPad8, .. } if menu => {
+                        VirtualKey::Digit(8)
+                    }
+                    Key { code: Number9, .. } | Key { code: NumPad9, .. } if menu => {
+                        VirtualKey::Digit(9)
+                    }
+                    Key {
+                        code: Up,
+                        shift: true,
+                        ..
+                    } => VirtualKey::SprintUp,
+                    Key {
+                        code: Down,
+                        shift: true,
+                        ..
+                    } => VirtualKey::SprintDown,
+                    Key {
+                        code: Left,
+                        shift: true,
+                        ..
+                    } => VirtualKey::SprintLeft,
+                    Key {
+                        code: Right,
+                        shift: true,
+                        ..
+                    } => VirtualKey::SprintRight,
                     Key { code: Up, .. } | Key { code: NumPad8, .. } => VirtualKey::MoveUp,
                     Key { code: Down, .. } | Key { code: NumPad2, .. } => VirtualKey::MoveDown,
                     Key { code: Left, .. } | Key { code: NumPad4, .. } => VirtualKey::MoveLeft,
                     Key { code: Right, .. } | Key { code: NumPad6, .. } => VirtualKey::MoveRight,
-                    Key { code: Escape, .. } => VirtualKey::Exit,
+                    Key { code: NumPad5, .. }
+                    | Key {
+                        code: Char,
+                        printable: '.',
+                        ..
+                    } => VirtualKey::Wait,
+                    Key {
+                        code: Char,
+                        printable: 'r',
+                        ..
+                    } => VirtualKey::Reload,
+                    Key {
+                        code: Char,
+                        printable: 'p',
+                        ..
+                    } => VirtualKey::Repair,
+                    Key {
+                        code: Char,
+                        printable: 'c',
+                        ..
+                    } => VirtualKey::CombatLog,
+                    Key {
+                        code: Char,
+                        printable: 'g',
+                        ..
+                    }
+                    | Key {
+                        code: Char,
+                        printable: ',',
+                        ..
+                    } => VirtualKey::PickItem,
+                    Key {
+                        code: Char,
+                        printable: 'd',
+                        ..
+                    } => VirtualKey::DropItem,
+                    Key {
+                        code: Char,
+                        printable: 'i',
+                        ..
+                    } => VirtualKey::Inventory,
+                    Key {
+                        code: Char,
+                        printable: 'l',
+                        ..
+                    } => VirtualKey::Loadout,
+                    Key {
+                        code: Char,
+                        printable: 'x',
+                        ..
+                    } => VirtualKey::Look,
+                    Key {
+                        code: Char,
+                        printable: 's',
+                        ..
+                    } => VirtualKey::Salvage,
+                    Key {
+                        code: Char,
+                        printable: 'm',
+                        ..
+                    } => VirtualKey::Crafting,
+                    Key {
+                        code: Char,
+                        printable: 't',
+                        ..
+                    } => VirtualKey::Trade,
+                    Key {
+                        code: Char,
+                        printable: 'o',
+                        ..
+                    } => VirtualKey::Explore,
+                    Key { code: Tab, .. } => VirtualKey::NextTarget,
+                    Key { code: Enter, .. }
+                    | Key {
+                        code: NumPadEnter, ..
+                    } => VirtualKey::Confirm,
+                    Key {
+                        code: Char,
+                        printable: 'f',
+                        ..
+                    } => {
+                        if normal {
+                            VirtualKey::Fire
+                        } else {
+                            VirtualKey::Confirm
+                        }
+                    }
+                    // escape backs out of whatever the player is doing before quitting
+                    Key { code: Escape, .. } => {
+                        if normal {
+                            VirtualKey::Exit
+                        } else {
+                            VirtualKey::Cancel
+                        }
+                    }
                     _ => VirtualKey::NoAction,
                 };
 
@@ -0,0 +1,392 @@
+use specs::{join::Join, Entities, Entity, Read, ReadStorage, System, Write, WriteStorage};
+
+use super::player_control::busy;
+use crate::components::{
+    AccuracyBonus, Ammo, AreaOfEffect, Consumable, DefenseBonus, Equippable, Hardpoints, Heat,
+    HitPoints, Illuminates, InBackpack, InflictsDamage, InflictsEffect, Item, MeleeBonus,
+    MobilityBonus, Name, Player, Position, ProvidesCooling, ProvidesHealing, ProvidesRepair,
+    RangedWeapon, Reloading, RevealsMap, Stackable, StatusEffects, Targeted, Teleports,
+    Unidentified, UpgradesWeapon, WantsToDrop, WantsToEquip, WantsToUseItem,
+};
+use crate::resources::{GameMode, InputMapping, MesLogs, PlayerTurn, VirtualKey};
+
+#[derive(Default)]
+pub struct InventoryMenu;
+
+#[derive(Clone, Copy)]
+enum ItemAction {
+    Use,
+    // how many off the stack, the player is asked if it isn't known yet
+    Drop(Option<i32>),
+    Equip,
+    Inspect,
+}
+
+impl<'a> System<'a> for InventoryMenu {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, HitPoints>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Ammo>,
+        ReadStorage<'a, Stackable>,
+        ReadStorage<'a, Unidentified>,
+        ReadStorage<'a, Consumable>,
+        // only read to describe the item
+        (
+            ReadStorage<'a, ProvidesHealing>,
+            ReadStorage<'a, ProvidesRepair>,
+            ReadStorage<'a, ProvidesCooling>,
+            ReadStorage<'a, RevealsMap>,
+            ReadStorage<'a, Teleports>,
+            ReadStorage<'a, AreaOfEffect>,
+            ReadStorage<'a, InflictsDamage>,
+            ReadStorage<'a, InflictsEffect>,
+            ReadStorage<'a, Illuminates>,
+        ),
+        (
+            ReadStorage<'a, MeleeBonus>,
+            ReadStorage<'a, DefenseBonus>,
+            ReadStorage<'a, AccuracyBonus>,
+            ReadStorage<'a, MobilityBonus>,
+            ReadStorage<'a, UpgradesWeapon>,
+            ReadStorage<'a, RangedWeapon>,
+        ),
+        ReadStorage<'a, Targeted>,
+        ReadStorage<'a, Equippable>,
+        ReadStorage<'a, Hardpoints>,
+        ReadStorage<'a, Heat>,
+        ReadStorage<'a, StatusEffects>,
+        ReadStorage<'a, Reloading>,
+        WriteStorage<'a, WantsToUseItem>,
+        WriteStorage<'a, WantsToDrop>,
+        WriteStorage<'a, WantsToEquip>,
+        Read<'a, InputMapping>,
+        Write<'a, GameMode>,
+        Write<'a, PlayerTurn>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player,
+            pos,
+            hp,
+            names,
+            items,
+            backpack,
+            ammo,
+            stacks,
+            unidentified,
+            consumables,
+            (
+                healing,
+                repair,
+                cooling,
+                reveals,
+                teleports,
+                areas,
+                damaging,
+                inflicting,
+                illuminating,
+            ),
+            (melee_bonus, defense_bonus, accuracy_bonus, mobility_bonus, upgrades, weapons),
+            targeted,
+            equippable,
+            hardpoints,
+            heat,
+            effects,
+            reloading,
+            mut wants_use,
+            mut wants_drop,
+            mut wants_equip,
+            im,
+            mut mode,
+            mut turn,
+            mut logs,
+        ) = data;
+
+        let player_entity = match (&entities, &player).join().next() {
+            Some((e, _)) => e,
+            None => return,
+        };
+
+        let key = match &im.key {
+            Some(key) => key,
+            None => return,
+        };
+
+        // the same order the inventory screen lists them in
+        let carried: Vec<Entity> = (&entities, &items, &backpack)
+            .join()
+            .filter(|(_, _, b)| b.owner == player_entity)
+            .map(|(e, _, _)| e)
+            .collect();
+
+        let (item, action) = match (*mode, key) {
+            (GameMode::Normal, VirtualKey::Inventory) => {
+                *mode = GameMode::Inventory {
+                    selected: None,
+                    dropping: false,
+                };
+                return;
+            }
+            (GameMode::Normal, VirtualKey::DropItem) => {
+                *mode = GameMode::Inventory {
+                    selected: None,
+                    dropping: true,
+                };
+                return;
+            }
+            (GameMode::Inventory { selected: None, .. }, VirtualKey::Cancel) => {
+                *mode = GameMode::Normal;
+                return;
+            }
+            (GameMode::Inventory { dropping, .. }, VirtualKey::Cancel) => {
+                *mode = GameMode::Inventory {
+                    selected: None,
+                    dropping,
+                };
+                return;
+            }
+            (
+                GameMode::Inventory {
+                    selected: None,
+                    dropping,
+                },
+                &VirtualKey::Letter(letter),
+            ) => {
+                let idx = match letter_index(letter) {
+                    Some(idx) if idx < carried.len() => idx,
+                    _ => return,
+                };
+                if !dropping {
+                    *mode = GameMode::Inventory {
+                        selected: Some(idx),
+                        dropping,
+                    };
+                    return;
+                }
+                (carried[idx], ItemAction::Drop(None))
+            }
+            (
+                GameMode::Inventory {
+                    selected: Some(idx),
+                    ..
+                },
+                &VirtualKey::Letter(letter),
+            ) => {
+                let action = match letter {
+                    'u' => ItemAction::Use,
+                    'd' => ItemAction::Drop(None),
+                    'e' => ItemAction::Equip,
+                    'i' => ItemAction::Inspect,
+                    _ => return,
+                };
+                match carried.get(idx) {
+                    Some(&item) => (item, action),
+                    None => return,
+                }
+            }
+            (GameMode::DropAmount { .. }, VirtualKey::Cancel) => {
+                *mode = GameMode::Inventory {
+                    selected: None,
+                    dropping: true,
+                };
+                return;
+            }
+            (GameMode::DropAmount { item, typed }, &VirtualKey::Digit(digit)) => {
+                let quantity = stacks.get(item).map_or(1, |s| s.quantity);
+                *mode = GameMode::DropAmount {
+                    item,
+                    typed: (typed * 10 + digit).min(quantity),
+                };
+                return;
+            }
+            // nothing typed drops the whole stack
+            (GameMode::DropAmount { item, typed }, VirtualKey::Confirm) => {
+                (item, ItemAction::Drop((typed > 0).then_some(typed)))
+            }
+            _ => return,
+        };
+
+        let item_name = Name::of(&names, item);
+
+        // looking costs nothing, and goes back to the list
+        if let ItemAction::Inspect = action {
+            let mut text = format!(
+                "{}: weighs {}",
+                item_name,
+                items.get(item).map_or(0, |i| i.weight)
+            );
+            if let Some(stack) = stacks.get(item) {
+                text += &format!(", {} of up to {} stacked", stack.quantity, stack.limit);
+            }
+            // what an unknown item does stays a mystery until it's identified
+            if unidentified.contains(item) {
+                text += ", not identified yet";
+            } else {
+                if let Some(ammo) = ammo.get(item) {
+                    text += &format!(", loads {}", ammo.kind.name());
+                }
+                if let Some(healing) = healing.get(item) {
+                    text += &format!(", mends {} structure", healing.amount);
+                }
+                if let Some(repair) = repair.get(item) {
+                    text += &format!(", restores {} armor", repair.armor);
+                }
+                if let Some(cooling) = cooling.get(item) {
+                    text += &format!(", vents {} heat", cooling.heat);
+                }
+                if reveals.contains(item) {
+                    text += ", maps the level";
+                }
+                if teleports.contains(item) {
+                    text += ", teleports";
+                }
+                if let Some(damage) = damaging.get(item) {
+                    text += &format!(
+                        ", deals {} {} damage",
+                        damage.damage,
+                        damage.damage_type.name()
+                    );
+                }
+                if let Some(inflicts) = inflicting.get(item) {
+                    text += &format!(
+                        ", {} for {} turns",
+                        inflicts.effect.kind.name(),
+                        inflicts.effect.turns
+                    );
+                }
+                if let Some(illuminates) = illuminating.get(item) {
+                    text += &format!(", lights up for {} turns", illuminates.turns);
+                }
+                if let Some(area) = areas.get(item) {
+                    text += &format!(", reaches {} tiles around", area.shape.reach());
+                }
+                if let Some(targeted) = targeted.get(item) {
+                    text += &format!(", thrown up to {} tiles", targeted.range);
+                }
+                if let Some(bonus) = melee_bonus.get(item) {
+                    text += &format!(", +{} melee damage", bonus.attack);
+                }
+                if let Some(bonus) = defense_bonus.get(item) {
+                    text += &format!(", +{} defense", bonus.defense);
+                }
+                if let Some(bonus) = accuracy_bonus.get(item) {
+                    text += &format!(", +{}% to hit", bonus.accuracy);
+                }
+                if let Some(bonus) = mobility_bonus.get(item) {
+                    text += &format!(", -{} sprint heat", bonus.sprint_heat);
+                }
+                if let Some(weapon) = weapons.get(item) {
+                    text += &format!(
+                        ", fires for {} {} damage up to {} tiles, {}/{} {} loaded",
+                        weapon.damage,
+                        weapon.damage_type.name(),
+                        weapon.range,
+                        weapon.loaded,
+                        weapon.magazine,
+                        weapon.ammo_kind.name()
+                    );
+                }
+                if let Some(upgrade) = upgrades.get(item) {
+                    text += &format!(
+                        ", upgrades a weapon by {:+} damage and {:+}% to hit",
+                        upgrade.damage, upgrade.accuracy
+                    );
+                }
+            }
+            if let Some(equippable) = equippable.get(item) {
+                let mounts: Vec<_> = equippable.mounts.iter().map(|m| m.name()).collect();
+                text += &format!(
+                    ", mounts on a {} {} hardpoint",
+                    equippable.size.name(),
+                    mounts.join(" or ")
+                );
+            }
+            logs.add_message(text + ".");
+            *mode = GameMode::Inventory {
+                selected: None,
+                dropping: false,
+            };
+            return;
+        }
+
+        *mode = GameMode::Normal;
+
+        if !hp.get(player_entity).is_some_and(HitPoints::alive) {
+            return;
+        }
+        if let Some(reason) = busy(player_entity, &names, &heat, &effects, &reloading) {
+            // waiting it out takes the turn, same as on the map
+            logs.add_message(reason);
+            turn.0 = true;
+            return;
+        }
+
+        match action {
+            ItemAction::Use => {
+                if let (true, Some(p)) = (targeted.contains(item), pos.get(player_entity)) {
+                    // the turn is only spent once a target is picked
+                    *mode = GameMode::Targeting {
+                        x: p.x,
+                        y: p.y,
+                        item: Some(item),
+                    };
+                } else if consumables.contains(item) {
+                    let _ = wants_use.insert(player_entity, WantsToUseItem { item, target: None });
+                    turn.0 = true;
+                } else if ammo.get(item).is_some() {
+                    logs.add_message(format!("Reload your weapon to use the {}.", item_name));
+                } else {
+                    logs.add_message(format!("The {} can't be used.", item_name));
+                }
+            }
+            ItemAction::Drop(quantity) => {
+                let stacked = stacks.get(item).map_or(1, |s| s.quantity);
+                match quantity {
+                    None if stacked > 1 => {
+                        *mode = GameMode::DropAmount { item, typed: 0 };
+                    }
+                    _ => {
+                        let quantity = quantity.unwrap_or(stacked);
+                        let _ = wants_drop.insert(player_entity, WantsToDrop { item, quantity });
+                        turn.0 = true;
+                    }
+                }
+            }
+            ItemAction::Equip => {
+                let fits = match (equippable.get(item), hardpoints.get(player_entity)) {
+                    (Some(equippable), Some(slots)) => slots.fitting(equippable).next().is_some(),
+                    _ => {
+                        logs.add_message(format!("The {} can't be equipped.", item_name));
+                        return;
+                    }
+                };
+                if fits {
+                    let _ = wants_equip.insert(player_entity, WantsToEquip { item });
+                    turn.0 = true;
+                } else {
+                    logs.add_message(format!(
+                        "The {} doesn't fit any of your hardpoints.",
+                        item_name
+                    ));
+                }
+            }
+            ItemAction::Inspect => {}
+        }
+    }
+}
+
+pub(super) fn letter_index(letter: char) -> Option<usize> {
+    if letter.is_ascii_lowercase() {
+        Some((letter as u8 - b'a') as usize)
+    } else {
+        None
+    }
+}
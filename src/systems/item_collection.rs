@@ -0,0 +1,76 @@
+use specs::{join::Join, Entities, ReadStorage, System, Write, WriteStorage};
+
+use crate::components::{InBackpack, Name, Position, Stackable, WantsToPickUp};
+use crate::consts::BACKPACK_CAPACITY;
+use crate::resources::MesLogs;
+
+#[derive(Default)]
+pub struct ItemCollection;
+
+impl<'a> System<'a> for ItemCollection {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, WantsToPickUp>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, InBackpack>,
+        WriteStorage<'a, Stackable>,
+        ReadStorage<'a, Name>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut wants_pick_up, mut pos, mut backpack, mut stacks, names, mut logs) =
+            data;
+
+        for (e, wants) in (&entities, &wants_pick_up).join() {
+            let item = wants.item;
+
+            // somebody else may have grabbed it first
+            if pos.get(item).is_none() {
+                continue;
+            }
+
+            // top up the stacks already carried first
+            let quantity = stacks.get(item).map_or(0, |s| s.quantity);
+            let mut left = quantity;
+            for stack in Stackable::matching(&stacks, &backpack, &names, &entities, e, item) {
+                if let Some(stack) = stacks.get_mut(stack) {
+                    let moved = (stack.limit - stack.quantity).min(left);
+                    stack.quantity += moved;
+                    left -= moved;
+                }
+            }
+            if let Some(picked) = stacks.get_mut(item) {
+                picked.quantity = left;
+            }
+
+            if stacks.contains(item) && left <= 0 {
+                let _ = entities.delete(item);
+            } else if InBackpack::count(&backpack, e) >= BACKPACK_CAPACITY {
+                let what = if left < quantity {
+                    "the rest of the"
+                } else {
+                    "the"
+                };
+                logs.add_message(format!(
+                    "{} has no room for {} {}.",
+                    Name::of(&names, e),
+                    what,
+                    Name::of(&names, item)
+                ));
+                continue;
+            } else {
+                pos.remove(item);
+                let _ = backpack.insert(item, InBackpack { owner: e });
+            }
+
+            logs.add_message(format!(
+                "{} picks up the {}.",
+                Name::of(&names, e),
+                Name::of(&names, item)
+            ));
+        }
+
+        wants_pick_up.clear();
+    }
+}
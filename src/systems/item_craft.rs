@@ -0,0 +1,128 @@
+use specs::{join::Join, Entities, LazyUpdate, Read, ReadStorage, System, Write, WriteStorage};
+
+use super::crafting::missing;
+use crate::components::{InBackpack, Name, Position, Stackable, WantsToCraft};
+use crate::consts::BACKPACK_CAPACITY;
+use crate::resources::{Identification, MesLogs, Recipes};
+use crate::spawner;
+
+#[derive(Default)]
+pub struct ItemCraft;
+
+impl<'a> System<'a> for ItemCraft {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, WantsToCraft>,
+        ReadStorage<'a, InBackpack>,
+        WriteStorage<'a, Stackable>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Position>,
+        Read<'a, Recipes>,
+        Read<'a, LazyUpdate>,
+        Write<'a, Identification>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut wants_craft,
+            backpack,
+            mut stacks,
+            names,
+            pos,
+            recipes,
+            lazy,
+            mut identification,
+            mut logs,
+        ) = data;
+
+        for (e, wants) in (&entities, &wants_craft).join() {
+            let recipe = match recipes.0.get(wants.recipe) {
+                Some(recipe) => recipe,
+                None => continue,
+            };
+            if missing(recipe, &backpack, &stacks, &names, e).is_some() {
+                continue;
+            }
+
+            // use up the ingredients, stack by stack
+            for (quantity, name) in &recipe.inputs {
+                let carried: Vec<_> = (&entities, &backpack, &names)
+                    .join()
+                    .filter(|(_, b, n)| b.owner == e && n.0 == *name)
+                    .map(|(item, _, _)| item)
+                    .collect();
+
+                let mut left = *quantity;
+                for item in carried {
+                    if left <= 0 {
+                        break;
+                    }
+                    match stacks.get_mut(item) {
+                        Some(stack) => {
+                            let taken = stack.quantity.min(left);
+                            left -= taken;
+                            if stack.take(taken) {
+                                let _ = entities.delete(item);
+                            }
+                        }
+                        None => {
+                            left -= 1;
+                            let _ = entities.delete(item);
+                        }
+                    }
+                }
+            }
+
+            let (quantity, name) = recipe.output.clone();
+            logs.add_message(format!(
+                "{} crafts {} {}.",
+                Name::of(&names, e),
+                quantity,
+                name
+            ));
+
+            // whoever made it knows what it is
+            identification.learn(&name);
+
+            // onto the stacks already carried first, the rest as a new item
+            let mut left = quantity;
+            for (_, stack, _, _) in (&entities, &mut stacks, &backpack, &names)
+                .join()
+                .filter(|(_, s, b, n)| b.owner == e && n.0 == name && s.quantity < s.limit)
+            {
+                let moved = (stack.limit - stack.quantity).min(left);
+                stack.quantity += moved;
+                left -= moved;
+            }
+            if left <= 0 {
+                continue;
+            }
+
+            // a full backpack leaves it on the floor
+            let room = InBackpack::count(&backpack, e) < BACKPACK_CAPACITY;
+            let at = pos.get(e).map(|p| (p.x, p.y));
+            lazy.exec_mut(move |world| {
+                let item = match spawner::item_stack(world, &name, left) {
+                    Some(item) => item,
+                    None => return,
+                };
+                match (room, at) {
+                    (false, Some((x, y))) => {
+                        let _ = world
+                            .write_storage::<Position>()
+                            .insert(item, Position { x, y });
+                    }
+                    _ => {
+                        let _ = world
+                            .write_storage::<InBackpack>()
+                            .insert(item, InBackpack { owner: e });
+                    }
+                }
+            });
+        }
+
+        wants_craft.clear();
+    }
+}
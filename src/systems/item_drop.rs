@@ -0,0 +1,86 @@
+use specs::{join::Join, Entities, LazyUpdate, Read, ReadStorage, System, Write, WriteStorage};
+
+use crate::components::{InBackpack, Name, Position, Stackable, Unidentified, WantsToDrop};
+use crate::resources::MesLogs;
+use crate::spawner;
+
+#[derive(Default)]
+pub struct ItemDrop;
+
+impl<'a> System<'a> for ItemDrop {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, WantsToDrop>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, InBackpack>,
+        WriteStorage<'a, Stackable>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Unidentified>,
+        Read<'a, LazyUpdate>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut wants_drop,
+            mut pos,
+            mut backpack,
+            mut stacks,
+            names,
+            unidentified,
+            lazy,
+            mut logs,
+        ) = data;
+
+        for (e, wants) in (&entities, &wants_drop).join() {
+            if backpack.get(wants.item).is_none_or(|b| b.owner != e) {
+                continue;
+            }
+
+            let (x, y) = match pos.get(e) {
+                Some(p) => (p.x, p.y),
+                None => continue,
+            };
+
+            // part of a stack comes off as a new stack of its own
+            if let Some(stack) = stacks.get_mut(wants.item) {
+                if wants.quantity < stack.quantity {
+                    stack.take(wants.quantity);
+
+                    let quantity = wants.quantity;
+                    logs.add_message(format!(
+                        "{} drops {} {}.",
+                        Name::of(&names, e),
+                        quantity,
+                        Name::of(&names, wants.item)
+                    ));
+
+                    // made from its real name, it looks the same if still unknown
+                    let real = match unidentified.get(wants.item) {
+                        Some(unknown) => unknown.real.clone(),
+                        None => Name::of(&names, wants.item),
+                    };
+                    lazy.exec_mut(move |world| {
+                        if let Some(dropped) = spawner::item_stack(world, &real, quantity) {
+                            let _ = world
+                                .write_storage::<Position>()
+                                .insert(dropped, Position { x, y });
+                        }
+                    });
+                    continue;
+                }
+            }
+
+            backpack.remove(wants.item);
+            let _ = pos.insert(wants.item, Position { x, y });
+            logs.add_message(format!(
+                "{} drops the {}.",
+                Name::of(&names, e),
+                Name::of(&names, wants.item)
+            ));
+        }
+
+        wants_drop.clear();
+    }
+}
@@ -0,0 +1,158 @@
+use specs::{join::Join, Entities, Entity, ReadStorage, System, Write, WriteStorage};
+
+use crate::components::{
+    Armament, Equippable, Equipped, Hardpoints, InBackpack, Name, RangedWeapon, Unidentified,
+    WantsToEquip, WantsToUnequip,
+};
+use crate::consts::BACKPACK_CAPACITY;
+use crate::resources::{Identification, MesLogs};
+
+#[derive(Default)]
+pub struct ItemEquip;
+
+impl<'a> System<'a> for ItemEquip {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, WantsToEquip>,
+        WriteStorage<'a, WantsToUnequip>,
+        ReadStorage<'a, Equippable>,
+        ReadStorage<'a, Hardpoints>,
+        ReadStorage<'a, Armament>,
+        WriteStorage<'a, RangedWeapon>,
+        WriteStorage<'a, Equipped>,
+        WriteStorage<'a, InBackpack>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Unidentified>,
+        Write<'a, Identification>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut wants_equip,
+            mut wants_unequip,
+            equippable,
+            hardpoints,
+            armaments,
+            mut weapons,
+            mut equipped,
+            mut backpack,
+            names,
+            unidentified,
+            mut identification,
+            mut logs,
+        ) = data;
+
+        for (e, wants) in (&entities, &wants_unequip).join() {
+            if equipped.get(wants.item).is_none_or(|q| q.owner != e) {
+                continue;
+            }
+            if InBackpack::count(&backpack, e) >= BACKPACK_CAPACITY {
+                logs.add_message("Your backpack is full.");
+                continue;
+            }
+
+            unmount(e, wants.item, &armaments, &mut weapons);
+            equipped.remove(wants.item);
+            let _ = backpack.insert(wants.item, InBackpack { owner: e });
+            logs.add_message(format!(
+                "{} takes off the {}.",
+                Name::of(&names, e),
+                Name::of(&names, wants.item)
+            ));
+        }
+
+        for (e, wants, slots) in (&entities, &wants_equip, &hardpoints).join() {
+            let item = wants.item;
+            if backpack.get(item).is_none_or(|b| b.owner != e) {
+                continue;
+            }
+            let fitting: Vec<_> = match equippable.get(item) {
+                Some(equippable) => slots.fitting(equippable).collect(),
+                None => continue,
+            };
+
+            // a mech carries one gun at a time, a new one takes the old one's place
+            let old_gun = (&entities, &equipped, &armaments)
+                .join()
+                .find(|(_, q, _)| q.owner == e)
+                .map(|(old, q, _)| (old, q.slot))
+                .filter(|_| armaments.contains(item));
+
+            // an empty hardpoint if there is one, otherwise swap out the first that fits
+            let slot = match old_gun
+                .map(|(_, slot)| slot)
+                .filter(|slot| fitting.contains(slot))
+                .or_else(|| {
+                    fitting
+                        .iter()
+                        .find(|&&slot| Equipped::on(&equipped, &entities, e, slot).is_none())
+                        .or_else(|| fitting.first())
+                        .copied()
+                }) {
+                Some(slot) => slot,
+                None => continue,
+            };
+
+            let mut taken_off: Vec<_> = Equipped::on(&equipped, &entities, e, slot)
+                .into_iter()
+                .collect();
+            if let Some((old, _)) = old_gun.filter(|(old, _)| !taken_off.contains(old)) {
+                taken_off.push(old);
+            }
+            // the new item frees up one spot in the backpack
+            if InBackpack::count(&backpack, e) + taken_off.len() > BACKPACK_CAPACITY + 1 {
+                logs.add_message("Your backpack is full.");
+                continue;
+            }
+
+            for old in taken_off {
+                unmount(e, old, &armaments, &mut weapons);
+                equipped.remove(old);
+                let _ = backpack.insert(old, InBackpack { owner: e });
+                logs.add_message(format!(
+                    "{} takes off the {}.",
+                    Name::of(&names, e),
+                    Name::of(&names, old)
+                ));
+            }
+
+            backpack.remove(item);
+            let _ = equipped.insert(item, Equipped { owner: e, slot });
+            if let Some(mut weapon) = weapons.remove(item) {
+                weapon.mount = slot.part();
+                let _ = weapons.insert(e, weapon);
+            }
+            logs.add_message(format!(
+                "{} mounts the {} on its {}.",
+                Name::of(&names, e),
+                Name::of(&names, item),
+                slot.name()
+            ));
+
+            // the mech's diagnostics know what it is once it's hooked up
+            if let Some(unknown) = unidentified.get(item) {
+                identification.learn(&unknown.real);
+            }
+        }
+
+        wants_equip.clear();
+        wants_unequip.clear();
+    }
+}
+
+// a gun coming off takes its workings back from the mech
+fn unmount(
+    owner: Entity,
+    item: Entity,
+    armaments: &ReadStorage<Armament>,
+    weapons: &mut WriteStorage<RangedWeapon>,
+) {
+    if !armaments.contains(item) {
+        return;
+    }
+    if let Some(weapon) = weapons.remove(owner) {
+        let _ = weapons.insert(item, weapon);
+    }
+}
@@ -0,0 +1,296 @@
+use rand::Rng;
+use specs::{join::Join, Entities, ReadStorage, System, Write, WriteExpect, WriteStorage};
+
+use super::movement::obstacle;
+use crate::components::{
+    AreaOfEffect, Attack, Blast, Consumable, Equipped, Flare, Heat, Hit, HitPoints, Identifies,
+    Illuminates, InBackpack, InflictsDamage, InflictsEffect, MechBody, Name, Position,
+    ProvidesCooling, ProvidesHealing, ProvidesRepair, RangedWeapon, RevealsMap, Stackable,
+    StatusEffects, SufferDamage, Targeted, Teleports, Unidentified, UpgradesWeapon, WantsToUseItem,
+};
+use crate::consts::{MAP_HEIGHT, MAP_WIDTH};
+use crate::map::Map;
+use crate::resources::{Identification, MesLogs};
+
+#[derive(Default)]
+pub struct ItemUse;
+
+impl<'a> System<'a> for ItemUse {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, WantsToUseItem>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Consumable>,
+        WriteStorage<'a, Stackable>,
+        (
+            ReadStorage<'a, ProvidesHealing>,
+            ReadStorage<'a, ProvidesRepair>,
+            ReadStorage<'a, ProvidesCooling>,
+            ReadStorage<'a, RevealsMap>,
+            ReadStorage<'a, Teleports>,
+            ReadStorage<'a, Identifies>,
+            ReadStorage<'a, UpgradesWeapon>,
+        ),
+        ReadStorage<'a, Targeted>,
+        ReadStorage<'a, AreaOfEffect>,
+        ReadStorage<'a, InflictsDamage>,
+        ReadStorage<'a, InflictsEffect>,
+        ReadStorage<'a, Illuminates>,
+        WriteStorage<'a, HitPoints>,
+        WriteStorage<'a, MechBody>,
+        WriteStorage<'a, Heat>,
+        WriteStorage<'a, RangedWeapon>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, StatusEffects>,
+        WriteStorage<'a, SufferDamage>,
+        WriteStorage<'a, Blast>,
+        WriteStorage<'a, Flare>,
+        ReadStorage<'a, Unidentified>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Equipped>,
+        WriteExpect<'a, Map>,
+        Write<'a, Identification>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut wants_use,
+            names,
+            consumables,
+            mut stacks,
+            (healing, repair, cooling, reveals, teleports, identifies, upgrades),
+            targeted,
+            areas,
+            damaging,
+            inflicting,
+            illuminating,
+            mut hp,
+            mut bodies,
+            mut heat,
+            mut weapons,
+            mut pos,
+            mut effects,
+            mut suffer,
+            mut blasts,
+            mut flares,
+            unidentified,
+            backpack,
+            equipped,
+            mut map,
+            mut identification,
+            mut logs,
+        ) = data;
+
+        for (user, wants) in (&entities, &wants_use).join() {
+            let item = wants.item;
+            let user_name = Name::of(&names, user);
+            let mut used = false;
+
+            logs.add_message(format!(
+                "{} uses the {}.",
+                user_name,
+                Name::of(&names, item)
+            ));
+
+            // every effect on the item applies, in turn
+            if let Some(healing) = healing.get(item) {
+                match (bodies.get_mut(user), hp.get_mut(user)) {
+                    (Some(body), Some(hp)) => {
+                        if let Some((kind, restored)) = body.heal(healing.amount) {
+                            hp.current = body.torso_structure();
+                            logs.add_message(format!(
+                                "{}'s {} is mended (+{}).",
+                                user_name,
+                                kind.name(),
+                                restored
+                            ));
+                            used = true;
+                        }
+                    }
+                    (None, Some(hp)) if hp.current < hp.max => {
+                        let restored = healing.amount.min(hp.max - hp.current);
+                        hp.current += restored;
+                        logs.add_message(format!("{} heals (+{}).", user_name, restored));
+                        used = true;
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(repair) = repair.get(item) {
+                let restored = bodies.get_mut(user).and_then(|b| {
+                    let (kind, restored) = b.repair(repair.armor)?;
+                    Some((kind, b.part(kind)?.armor_type, restored))
+                });
+                if let Some((kind, plating, restored)) = restored {
+                    logs.add_message(format!(
+                        "{} patches up its {} {} plating (+{}).",
+                        user_name,
+                        kind.name(),
+                        plating.name(),
+                        restored
+                    ));
+                    used = true;
+                }
+            }
+
+            if let Some(cooling) = cooling.get(item) {
+                if let Some(heat) = heat.get_mut(user) {
+                    if heat.current > 0 {
+                        let dumped = cooling.heat.min(heat.current);
+                        heat.current -= dumped;
+                        logs.add_message(format!("{} vents {} heat.", user_name, dumped));
+                        used = true;
+                    }
+                }
+            }
+
+            if reveals.contains(item) {
+                for column in map.tiles.iter_mut() {
+                    for tile in column.iter_mut() {
+                        tile.explored = true;
+                    }
+                }
+                logs.add_message("The layout of the whole level comes up on the display.");
+                used = true;
+            }
+
+            if teleports.contains(item) {
+                // try a few random spots, anywhere free will do
+                let mut rng = rand::thread_rng();
+                let destination = (0..100)
+                    .map(|_| (rng.gen_range(0, MAP_WIDTH), rng.gen_range(0, MAP_HEIGHT)))
+                    .find(|&at| obstacle(&map, &entities, &pos, &hp, user, at).is_none());
+
+                if let (Some((x, y)), Some(p)) = (destination, pos.get_mut(user)) {
+                    p.x = x;
+                    p.y = y;
+                    logs.add_message(format!("{} blinks out and reappears elsewhere.", user_name));
+                    used = true;
+                }
+            }
+
+            if let (Some(upgrade), Some(weapon)) = (upgrades.get(item), weapons.get_mut(user)) {
+                weapon.damage += upgrade.damage;
+                weapon.accuracy += upgrade.accuracy;
+                logs.add_message(format!("{}'s {} is upgraded.", user_name, weapon.name));
+                used = true;
+            }
+
+            if identifies.contains(item) {
+                let carried: Vec<_> = (&entities, &unidentified)
+                    .join()
+                    .filter(|(e, _)| {
+                        backpack.get(*e).is_some_and(|b| b.owner == user)
+                            || equipped.get(*e).is_some_and(|q| q.owner == user)
+                    })
+                    .map(|(_, u)| u.real.clone())
+                    .collect();
+                for real in &carried {
+                    identification.learn(real);
+                }
+                used |= !carried.is_empty();
+            }
+
+            // thrown items do their thing where they land
+            let landing = match (targeted.get(item), wants.target, pos.get(user)) {
+                (Some(targeted), Some(target), Some(p)) => {
+                    Some(map.landing((p.x, p.y), target, targeted.range))
+                }
+                _ => None,
+            };
+
+            if let Some(at) = landing {
+                let area = areas.get(item).map(|a| a.shape);
+                let tiles = area.map_or(vec![at], |shape| map.area(shape, at, at));
+                let caught: Vec<_> = (&entities, &pos, &hp)
+                    .join()
+                    .filter(|(_, p, hp)| hp.alive() && tiles.contains(&(p.x, p.y)))
+                    .map(|(e, _, _)| e)
+                    .collect();
+
+                if let Some(damage) = damaging.get(item) {
+                    let attack = Attack::new(&Name::of(&names, item));
+                    match area {
+                        // blasts fade out towards their edge, and flash on screen
+                        Some(shape) => {
+                            let _ = blasts.insert(
+                                entities.create(),
+                                Blast {
+                                    center: at,
+                                    toward: at,
+                                    shape,
+                                    damage: damage.damage,
+                                    damage_type: damage.damage_type,
+                                    effect: None,
+                                    knockback: 0,
+                                    source: user,
+                                    attack,
+                                },
+                            );
+                        }
+                        None => {
+                            for &victim in &caught {
+                                SufferDamage::add_hit(
+                                    &mut suffer,
+                                    victim,
+                                    Hit {
+                                        source: user,
+                                        amount: damage.damage,
+                                        damage_type: damage.damage_type,
+                                        effect: None,
+                                        attack: attack.clone(),
+                                    },
+                                );
+                            }
+                        }
+                    }
+                }
+
+                if let Some(inflicts) = inflicting.get(item) {
+                    for &victim in &caught {
+                        logs.add_message(format!(
+                            "{} is {}!",
+                            Name::of(&names, victim),
+                            inflicts.effect.kind.name()
+                        ));
+                        StatusEffects::inflict(&mut effects, victim, inflicts.effect);
+                    }
+                }
+
+                if let Some(illuminates) = illuminating.get(item) {
+                    let _ = flares.insert(
+                        entities.create(),
+                        Flare {
+                            tiles,
+                            turns_left: illuminates.turns,
+                        },
+                    );
+                    logs.add_message("A flare lights up the area.");
+                }
+
+                used = true;
+            }
+
+            // whatever it did gives it away
+            if used {
+                if let Some(unknown) = unidentified.get(item) {
+                    identification.learn(&unknown.real);
+                }
+            }
+
+            if !used {
+                logs.add_message("Nothing happens.");
+            } else if consumables.contains(item) {
+                // one off the stack, the item is gone once the stack is
+                if stacks.get_mut(item).is_none_or(|s| s.take(1)) {
+                    let _ = entities.delete(item);
+                }
+            }
+        }
+
+        wants_use.clear();
+    }
+}
@@ -0,0 +1,94 @@
+use specs::{join::Join, Entities, ReadExpect, ReadStorage, System, Write, WriteStorage};
+
+use super::movement::{obstacle, Obstacle};
+use crate::components::{
+    Attack, DamageType, Hit, HitPoints, Knockback, Name, Position, SufferDamage,
+};
+use crate::consts::COLLISION_DAMAGE;
+use crate::map::Map;
+use crate::resources::MesLogs;
+
+#[derive(Default)]
+pub struct KnockbackPush;
+
+impl<'a> System<'a> for KnockbackPush {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Knockback>,
+        WriteStorage<'a, Position>,
+        ReadStorage<'a, HitPoints>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, SufferDamage>,
+        ReadExpect<'a, Map>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut knockback, mut pos, hp, names, mut suffer, map, mut logs) = data;
+
+        let pushes: Vec<_> = (&entities, &knockback)
+            .join()
+            .map(|(e, k)| (e, k.dx, k.dy, k.force))
+            .collect();
+        knockback.clear();
+
+        for (pushed, dx, dy, force) in pushes {
+            let mut moved = false;
+
+            for _ in 0..force {
+                let (tx, ty) = match pos.get(pushed) {
+                    Some(p) => (p.x + dx, p.y + dy),
+                    None => break,
+                };
+
+                // whatever stops the push hurts, on both sides
+                let mut crashed = vec![pushed];
+                match obstacle(&map, &entities, &pos, &hp, pushed, (tx, ty)) {
+                    Some(Obstacle::Wall) => {
+                        logs.add_message(format!(
+                            "{} slams into the wall!",
+                            Name::of(&names, pushed)
+                        ));
+                    }
+                    Some(Obstacle::Entity(other)) => {
+                        logs.add_message(format!(
+                            "{} crashes into {}!",
+                            Name::of(&names, pushed),
+                            Name::of(&names, other)
+                        ));
+                        crashed.push(other);
+                    }
+                    None => {
+                        if !moved {
+                            logs.add_message(format!(
+                                "{} is knocked back.",
+                                Name::of(&names, pushed)
+                            ));
+                            moved = true;
+                        }
+                        if let Some(p) = pos.get_mut(pushed) {
+                            p.x = tx;
+                            p.y = ty;
+                        }
+                        continue;
+                    }
+                }
+
+                for e in crashed {
+                    SufferDamage::add_hit(
+                        &mut suffer,
+                        e,
+                        Hit {
+                            source: e,
+                            amount: COLLISION_DAMAGE,
+                            damage_type: DamageType::Kinetic,
+                            effect: None,
+                            attack: Attack::new("collision"),
+                        },
+                    );
+                }
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use specs::{join::Join, Entities, Read, ReadStorage, System, Write, WriteStorage};
+
+use super::inventory::letter_index;
+use super::player_control::busy;
+use crate::components::{
+    Equipped, Hardpoints, Heat, HitPoints, InBackpack, Name, Player, Reloading, StatusEffects,
+    WantsToUnequip,
+};
+use crate::consts::BACKPACK_CAPACITY;
+use crate::resources::{GameMode, InputMapping, MesLogs, PlayerTurn, VirtualKey};
+
+#[derive(Default)]
+pub struct LoadoutMenu;
+
+impl<'a> System<'a> for LoadoutMenu {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, HitPoints>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Hardpoints>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Heat>,
+        ReadStorage<'a, StatusEffects>,
+        ReadStorage<'a, Reloading>,
+        WriteStorage<'a, WantsToUnequip>,
+        Read<'a, InputMapping>,
+        Write<'a, GameMode>,
+        Write<'a, PlayerTurn>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player,
+            hp,
+            names,
+            hardpoints,
+            equipped,
+            backpack,
+            heat,
+            effects,
+            reloading,
+            mut wants_unequip,
+            im,
+            mut mode,
+            mut turn,
+            mut logs,
+        ) = data;
+
+        let (player_entity, slots) = match (&entities, &hardpoints, &player).join().next() {
+            Some((e, slots, _)) => (e, slots),
+            None => return,
+        };
+
+        let letter = match (*mode, &im.key) {
+            (GameMode::Normal, Some(VirtualKey::Loadout)) => {
+                *mode = GameMode::Loadout;
+                return;
+            }
+            (GameMode::Loadout, Some(VirtualKey::Cancel)) => {
+                *mode = GameMode::Normal;
+                return;
+            }
+            (GameMode::Loadout, Some(VirtualKey::Letter(letter))) => *letter,
+            _ => return,
+        };
+
+        // the same order the loadout screen lists them in
+        let slot = match letter_index(letter).and_then(|idx| slots.slots.get(idx)) {
+            Some(&(slot, _)) => slot,
+            None => return,
+        };
+
+        *mode = GameMode::Normal;
+
+        let item = match Equipped::on(&equipped, &entities, player_entity, slot) {
+            Some(item) => item,
+            None => {
+                logs.add_message(format!("Nothing is mounted on your {}.", slot.name()));
+                return;
+            }
+        };
+
+        if !hp.get(player_entity).is_some_and(HitPoints::alive) {
+            return;
+        }
+        if let Some(reason) = busy(player_entity, &names, &heat, &effects, &reloading) {
+            logs.add_message(reason);
+            turn.0 = true;
+            return;
+        }
+        if InBackpack::count(&backpack, player_entity) >= BACKPACK_CAPACITY {
+            logs.add_message("Your backpack is full.");
+            return;
+        }
+
+        let _ = wants_unequip.insert(player_entity, WantsToUnequip { item });
+        turn.0 = true;
+    }
+}
@@ -0,0 +1,92 @@
+use specs::{join::Join, Entities, Read, ReadExpect, ReadStorage, System, Write, WriteStorage};
+
+use crate::components::{HitPoints, Player, Position, Travel};
+use crate::map::Map;
+use crate::pathfinding::Pathfinder;
+use crate::resources::{GameMode, InputMapping, MesLogs, VirtualKey};
+
+// what a step into the unknown costs when travelling
+const UNEXPLORED_COST: i32 = 20;
+
+#[derive(Default)]
+pub struct LookView;
+
+impl<'a> System<'a> for LookView {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, HitPoints>,
+        WriteStorage<'a, Travel>,
+        ReadExpect<'a, Map>,
+        Read<'a, InputMapping>,
+        Write<'a, GameMode>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, player, pos, hp, mut travel, map, im, mut mode, mut logs) = data;
+
+        match (*mode, &im.key) {
+            (GameMode::Normal, Some(VirtualKey::Look)) => {
+                if let Some((p, _)) = (&pos, &player).join().next() {
+                    *mode = GameMode::Look { x: p.x, y: p.y };
+                }
+            }
+            (GameMode::Look { x, y }, Some(key)) => {
+                let (dx, dy) = match key {
+                    VirtualKey::MoveUp => (0, -1),
+                    VirtualKey::MoveDown => (0, 1),
+                    VirtualKey::MoveLeft => (-1, 0),
+                    VirtualKey::MoveRight => (1, 0),
+                    VirtualKey::Look | VirtualKey::Cancel => {
+                        *mode = GameMode::Normal;
+                        return;
+                    }
+                    // travel over to the cursor, around anything standing in the way
+                    VirtualKey::Confirm => {
+                        *mode = GameMode::Normal;
+                        let (e, from) = match (&entities, &pos, &hp, &player).join().next() {
+                            Some((e, p, hp, _)) if hp.alive() => (e, (p.x, p.y)),
+                            _ => return,
+                        };
+                        if !map.in_bounds(x, y) || !map.tile(x, y).explored {
+                            logs.add_message("You don't know the way there.");
+                            return;
+                        }
+
+                        let occupied = (&pos, &hp).join().map(|(p, _)| (p.x, p.y));
+                        // keep to known ground when there's a way along it
+                        let path = Pathfinder::new(&map)
+                            .avoiding(occupied)
+                            .costing(|(x, y)| {
+                                if map.tile(x, y).explored {
+                                    1
+                                } else {
+                                    UNEXPLORED_COST
+                                }
+                            })
+                            .path(from, (x, y));
+                        match path {
+                            Some(mut steps) if !steps.is_empty() => {
+                                steps.reverse();
+                                let _ = travel.insert(e, Travel { steps });
+                            }
+                            Some(_) => {}
+                            None => logs.add_message("You can't find a way there."),
+                        }
+                        return;
+                    }
+                    _ => return,
+                };
+                if map.in_bounds(x + dx, y + dy) {
+                    *mode = GameMode::Look {
+                        x: x + dx,
+                        y: y + dy,
+                    };
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use rand::Rng;
+use specs::{join::Join, Entities, ReadStorage, System, Write, WriteStorage};
+
+use crate::components::{
+    Attack, DefenseBonus, Equipped, Heat, Hit, HitPoints, MechBody, Melee, MeleeBonus, Name,
+    Position, SufferDamage, WantsToMelee,
+};
+use crate::consts::MELEE_HEAT;
+use crate::map::distance;
+use crate::resources::{AttackRecord, CombatLog, HitOutcome, MesLogs};
+
+#[derive(Default)]
+pub struct MeleeCombat;
+
+impl<'a> System<'a> for MeleeCombat {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, WantsToMelee>,
+        ReadStorage<'a, Melee>,
+        ReadStorage<'a, HitPoints>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, MechBody>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, MeleeBonus>,
+        ReadStorage<'a, DefenseBonus>,
+        WriteStorage<'a, Heat>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, SufferDamage>,
+        Write<'a, MesLogs>,
+        Write<'a, CombatLog>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut wants_melee,
+            melee,
+            hp,
+            pos,
+            bodies,
+            equipped,
+            melee_bonus,
+            defense_bonus,
+            mut heat,
+            names,
+            mut suffer,
+            mut logs,
+            mut combat_log,
+        ) = data;
+
+        for (attacker, wants, attack) in (&entities, &wants_melee, &melee).join() {
+            let target = wants.target;
+            if !hp.get(target).is_some_and(HitPoints::alive) {
+                continue;
+            }
+
+            // the target may have stepped away since the attack was planned
+            if let (Some(a), Some(t)) = (pos.get(attacker), pos.get(target)) {
+                if distance((a.x, a.y), (t.x, t.y)) >= 2.0 {
+                    continue;
+                }
+            }
+
+            if bodies.get(attacker).is_some_and(|b| !b.can_punch()) {
+                logs.add_message(format!(
+                    "{} has no arms left to attack with!",
+                    Name::of(&names, attacker)
+                ));
+                continue;
+            }
+
+            // swinging heavy arms heats the mech up, and a hot mech swings wide
+            let mut penalty = 0;
+            if let Some(heat) = heat.get_mut(attacker) {
+                penalty = heat.accuracy_penalty();
+                heat.add(MELEE_HEAT);
+            }
+            let defense = melee.get(target).map_or(0, |m| m.defense)
+                + Equipped::bonus(&equipped, &defense_bonus, &bodies, target, |b| b.defense);
+            let bonus = Equipped::bonus(&equipped, &melee_bonus, &bodies, attacker, |b| b.attack);
+            let roll = rand::thread_rng().gen_range(0, 100);
+            let swing = Attack::new("melee")
+                .rolled(roll, 100 - penalty)
+                .modifier("heat", -penalty)
+                .modifier("defense", -defense)
+                .modifier("equipment", bonus);
+
+            if !swing.hits() {
+                logs.add_message(format!(
+                    "{} attacks {} but misses.",
+                    Name::of(&names, attacker),
+                    Name::of(&names, target)
+                ));
+                combat_log.record(AttackRecord {
+                    attacker: Name::of(&names, attacker),
+                    target: Name::of(&names, target),
+                    attack: swing,
+                    outcome: None,
+                });
+                continue;
+            }
+
+            // a simple formula for attack damage
+            let damage = attack.attack + bonus - defense;
+            if damage > 0 {
+                SufferDamage::add_hit(
+                    &mut suffer,
+                    target,
+                    Hit {
+                        source: attacker,
+                        amount: damage,
+                        damage_type: attack.damage_type,
+                        effect: attack.on_hit,
+                        attack: swing,
+                    },
+                );
+            } else {
+                logs.add_message(format!(
+                    "{} attacks {} but it has no effect!",
+                    Name::of(&names, attacker),
+                    Name::of(&names, target)
+                ));
+                combat_log.record(AttackRecord {
+                    attacker: Name::of(&names, attacker),
+                    target: Name::of(&names, target),
+                    attack: swing,
+                    outcome: Some(HitOutcome {
+                        base: damage,
+                        damage_type: attack.damage_type.name(),
+                        multiplier: 1.0,
+                        location: None,
+                        absorbed: 0,
+                        damage: 0,
+                    }),
+                });
+            }
+        }
+
+        wants_melee.clear();
+    }
+}
@@ -1,12 +1,93 @@
 mod tcod_integration;
 pub use tcod_integration::TcodIntegration;
 
+mod player_control;
+pub use player_control::PlayerControl;
+
+mod reload;
+pub use reload::Reload;
+
+mod inventory;
+pub use inventory::InventoryMenu;
+
+mod item_collection;
+pub use item_collection::ItemCollection;
+
+mod item_drop;
+pub use item_drop::ItemDrop;
+
+mod item_use;
+pub use item_use::ItemUse;
+
+mod item_equip;
+pub use item_equip::ItemEquip;
+
+mod loadout;
+pub use loadout::LoadoutMenu;
+
+mod crafting;
+pub use crafting::CraftingMenu;
+
+mod item_craft;
+pub use item_craft::ItemCraft;
+
+mod salvage;
+pub use salvage::Salvage;
+
+mod shop;
+pub use shop::ShopMenu;
+
+mod trade;
+pub use trade::Trade;
+
+mod identification;
+pub use identification::ItemIdentification;
+
+mod targeting;
+pub use targeting::Targeting;
+
+mod monster_ai;
+pub use monster_ai::MonsterAi;
+
 mod movement;
 pub use movement::Movement;
 
+mod visibility;
+pub use visibility::Visibility;
+
+mod melee_combat;
+pub use melee_combat::MeleeCombat;
+
+mod ranged_combat;
+pub use ranged_combat::RangedCombat;
+
+mod explosions;
+pub use explosions::Explosions;
+
+mod knockback;
+pub use knockback::KnockbackPush;
+
+mod heat;
+pub use heat::HeatManagement;
+
+mod status_effects;
+pub use status_effects::StatusTick;
+
+mod damage;
+pub use damage::Damage;
+
+mod death;
+pub use death::Death;
+
 mod input;
 pub use input::Input;
 
+mod combat_log;
+pub use combat_log::CombatLogView;
+
+mod look;
+pub use look::LookView;
+
 mod exit;
 pub use exit::Exit;
 
@@ -0,0 +1,332 @@
+use std::collections::HashSet;
+use std::mem;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use specs::{join::Join, Entities, Read, ReadExpect, ReadStorage, System, WriteStorage};
+
+use super::player_control::busy;
+use crate::components::{
+    Ai, AiState, Ammo, EffectKind, Explosive, Heat, HitPoints, InBackpack, MechBody, Name, Player,
+    Position, RangedWeapon, Reloading, Stackable, StatusEffects, Viewshed, WantsToMelee,
+    WantsToMove, WantsToShoot,
+};
+use crate::consts::{COVER_STEPS, KEEP_AWAY, SEARCH_TURNS, WANDER_CHANCE};
+use crate::map::{distance, Map};
+use crate::pathfinding::{DijkstraMap, Pathfinder};
+use crate::resources::{DebugMode, PlayerTurn};
+
+/// Monsters take their turn right after the player's, carried out next frame
+#[derive(Default)]
+pub struct MonsterAi;
+
+impl<'a> System<'a> for MonsterAi {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Ai>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, HitPoints>,
+        ReadStorage<'a, Viewshed>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Heat>,
+        ReadStorage<'a, StatusEffects>,
+        WriteStorage<'a, Reloading>,
+        ReadStorage<'a, Explosive>,
+        ReadStorage<'a, RangedWeapon>,
+        ReadStorage<'a, MechBody>,
+        ReadStorage<'a, Ammo>,
+        ReadStorage<'a, Stackable>,
+        ReadStorage<'a, InBackpack>,
+        WriteStorage<'a, WantsToMove>,
+        WriteStorage<'a, WantsToMelee>,
+        WriteStorage<'a, WantsToShoot>,
+        ReadExpect<'a, Map>,
+        Read<'a, PlayerTurn>,
+        Read<'a, DebugMode>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut ai,
+            player,
+            pos,
+            hp,
+            viewsheds,
+            names,
+            heat,
+            effects,
+            mut reloading,
+            explosive,
+            weapons,
+            bodies,
+            ammo,
+            stacks,
+            backpack,
+            mut wants_move,
+            mut wants_melee,
+            mut wants_shoot,
+            map,
+            turn,
+            debug,
+        ) = data;
+
+        if !turn.0 {
+            return;
+        }
+
+        let (player_entity, player_pos) = match (&entities, &pos, &hp, &player)
+            .join()
+            .find(|(_, _, hp, _)| hp.alive())
+        {
+            Some((e, p, _, _)) => (e, (p.x, p.y)),
+            None => return,
+        };
+
+        // monsters walk around each other rather than queueing up
+        let occupied: HashSet<_> = (&pos, &hp)
+            .join()
+            .filter(|(_, hp)| hp.alive())
+            .map(|(p, _)| (p.x, p.y))
+            .collect();
+
+        let mut flee_map = None;
+        let mut rng = rand::thread_rng();
+
+        for (e, ai, p, hp, viewshed) in (&entities, &mut ai, &pos, &hp, &viewsheds).join() {
+            if !hp.alive() || busy(e, &names, &heat, &effects, &reloading).is_some() {
+                continue;
+            }
+
+            let at = (p.x, p.y);
+            let sees_player = viewshed.can_see(player_pos.0, player_pos.1);
+            // badly hurt monsters run for it, bombs don't know fear
+            let hurt = hp.current * 4 <= hp.max && !explosive.contains(e);
+
+            let next = match ai.state {
+                _ if sees_player && hurt => AiState::Flee,
+                _ if sees_player => AiState::Hunt {
+                    last_seen: player_pos,
+                },
+                AiState::Hunt { last_seen } => AiState::Search {
+                    last_seen,
+                    turns_left: SEARCH_TURNS,
+                },
+                AiState::Search {
+                    last_seen,
+                    turns_left,
+                } if turns_left > 0 && at != last_seen => AiState::Search {
+                    last_seen,
+                    turns_left: turns_left - 1,
+                },
+                // close enough will do, somebody else may be standing right on it
+                AiState::Wander { goal } if distance(at, goal) >= 2.0 => AiState::Wander { goal },
+                AiState::Idle if rng.gen_range(0, 100) < WANDER_CHANCE => {
+                    match map.rooms.choose(&mut rng) {
+                        Some(room) => AiState::Wander {
+                            goal: room.rand_inside(),
+                        },
+                        None => AiState::Idle,
+                    }
+                }
+                // out of sight is as good as safe, and searches and walks come to an end
+                _ => AiState::Idle,
+            };
+
+            if debug.0 && mem::discriminant(&next) != mem::discriminant(&ai.state) {
+                println!(
+                    "{} #{} at {:?}: {} -> {}",
+                    Name::of(&names, e),
+                    e.id(),
+                    at,
+                    ai.state.name(),
+                    next.name()
+                );
+            }
+            ai.state = next;
+
+            // shooters hunt from a distance for as long as the gun works
+            let hunting = sees_player && matches!(ai.state, AiState::Hunt { .. });
+            let tactic = match weapons.get(e) {
+                Some(weapon)
+                    if hunting
+                        && bodies.get(e).is_none_or(|b| b.operational(weapon.mount))
+                        && effects.get(e).is_none_or(|s| !s.has(EffectKind::Emp)) =>
+                {
+                    let rounds = Ammo::carried(&ammo, &stacks, &backpack, e, weapon.ammo_kind);
+                    ranged(
+                        &map,
+                        &occupied,
+                        &mut flee_map,
+                        at,
+                        player_pos,
+                        weapon,
+                        rounds,
+                    )
+                }
+                _ => None,
+            };
+
+            let adjacent = sees_player && distance(at, player_pos) < 2.0;
+            let step = match tactic {
+                Some(Tactic::Shoot) => {
+                    let _ = wants_shoot.insert(e, WantsToShoot { target: player_pos });
+                    continue;
+                }
+                Some(Tactic::Reload(turns_left)) => {
+                    let _ = reloading.insert(e, Reloading { turns_left });
+                    continue;
+                }
+                Some(Tactic::Move(step)) => Some(step),
+                None => match ai.state {
+                    AiState::Idle => None,
+                    AiState::Flee => flee(&mut flee_map, &map, player_pos)
+                        .downhill(at, |next| occupied.contains(&next)),
+                    AiState::Hunt { .. } if adjacent => None,
+                    AiState::Hunt { last_seen: goal }
+                    | AiState::Search {
+                        last_seen: goal, ..
+                    }
+                    | AiState::Wander { goal } => step_towards(&map, &occupied, at, goal),
+                },
+            };
+
+            // nowhere to wander off to after all
+            if step.is_none() && matches!(ai.state, AiState::Wander { .. }) {
+                ai.state = AiState::Idle;
+            }
+
+            match step {
+                Some((x, y)) => {
+                    let _ = wants_move.insert(
+                        e,
+                        WantsToMove {
+                            dx: x - at.0,
+                            dy: y - at.1,
+                            sprint: false,
+                        },
+                    );
+                }
+                // cornered or not, anything right next to the player fights
+                None if adjacent => {
+                    let _ = wants_melee.insert(
+                        e,
+                        WantsToMelee {
+                            target: player_entity,
+                        },
+                    );
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+enum Tactic {
+    Shoot,
+    // turns the fresh magazine takes
+    Reload(i32),
+    Move((i32, i32)),
+}
+
+// only worked out once somebody needs to run away
+fn flee<'m>(
+    flee_map: &'m mut Option<DijkstraMap>,
+    map: &Map,
+    threat: (i32, i32),
+) -> &'m DijkstraMap {
+    flee_map.get_or_insert_with(|| DijkstraMap::new(map, &[threat], |_| true).inverted())
+}
+
+// what a shooter does about `target`, None when it might as well close in and fight
+fn ranged(
+    map: &Map,
+    occupied: &HashSet<(i32, i32)>,
+    flee_map: &mut Option<DijkstraMap>,
+    at: (i32, i32),
+    target: (i32, i32),
+    weapon: &RangedWeapon,
+    rounds: i32,
+) -> Option<Tactic> {
+    if weapon.loaded <= 0 {
+        return if rounds > 0 {
+            Some(Tactic::Reload(weapon.reload_turns))
+        } else {
+            None
+        };
+    }
+
+    // short guns can't afford to back off as far
+    let keep_away = (weapon.range - 1).clamp(1, KEEP_AWAY) as f32;
+    let clear = clear_shot(map, occupied, at, target, weapon.range);
+
+    // too close for comfort, back off while there's room to
+    if distance(at, target) < keep_away {
+        let away = flee(flee_map, map, target).downhill(at, |next| occupied.contains(&next));
+        return match away {
+            Some(step) => Some(Tactic::Move(step)),
+            None if clear => Some(Tactic::Shoot),
+            None => None,
+        };
+    }
+
+    let spots: Vec<_> = (target.0 - weapon.range..=target.0 + weapon.range)
+        .flat_map(|x| (target.1 - weapon.range..=target.1 + weapon.range).map(move |y| (x, y)))
+        .filter(|&spot| spot == at || !occupied.contains(&spot))
+        .filter(|&spot| !map.blocked(spot.0, spot.1) && distance(spot, target) >= keep_away)
+        .filter(|&spot| clear_shot(map, occupied, spot, target, weapon.range))
+        .collect();
+    let covered: Vec<_> = spots
+        .iter()
+        .copied()
+        .filter(|&(x, y)| map.sheltered(x, y))
+        .collect();
+    let walkable = |tile| tile == at || !occupied.contains(&tile);
+
+    if covered.contains(&at) {
+        return Some(Tactic::Shoot);
+    }
+    // worth a step or two to fire from behind a wall
+    let cover = DijkstraMap::new(map, &covered, walkable);
+    if cover.value(at).is_some_and(|steps| steps <= COVER_STEPS) {
+        if let Some(step) = cover.downhill(at, |next| occupied.contains(&next)) {
+            return Some(Tactic::Move(step));
+        }
+    }
+    if clear {
+        return Some(Tactic::Shoot);
+    }
+
+    DijkstraMap::new(map, &spots, walkable)
+        .downhill(at, |next| occupied.contains(&next))
+        .map(Tactic::Move)
+}
+
+// nothing but the target itself in the way, friends included
+fn clear_shot(
+    map: &Map,
+    occupied: &HashSet<(i32, i32)>,
+    from: (i32, i32),
+    target: (i32, i32),
+    range: i32,
+) -> bool {
+    match map.line_of_fire(from, target, range).split_last() {
+        Some((&last, before)) => last == target && before.iter().all(|t| !occupied.contains(t)),
+        None => false,
+    }
+}
+
+// squeeze past whoever is in the way if there's room, otherwise wait behind them
+fn step_towards(
+    map: &Map,
+    occupied: &HashSet<(i32, i32)>,
+    from: (i32, i32),
+    to: (i32, i32),
+) -> Option<(i32, i32)> {
+    Pathfinder::new(map)
+        .avoiding(occupied.iter().cloned())
+        .path(from, to)
+        .or_else(|| Pathfinder::new(map).path(from, to))
+        .and_then(|path| path.first().cloned())
+}
@@ -1,28 +1,145 @@
-use specs::{join::Join, Read, ReadStorage, System, WriteStorage};
+use std::ops::Deref;
 
-use crate::components::{Player, Position};
-use crate::resources::{InputMapping, VirtualKey};
+use specs::storage::MaskedStorage;
+use specs::{
+    join::Join, Entities, Entity, ReadExpect, ReadStorage, Storage, System, Write, WriteStorage,
+};
+
+use crate::components::{
+    Ai, Equipped, Heat, HitPoints, MechBody, MobilityBonus, Name, PartKind, Position, Vendor,
+    WantsToMelee, WantsToMove,
+};
+use crate::consts::SPRINT_HEAT;
+use crate::map::Map;
+use crate::resources::MesLogs;
+
+/// What keeps an entity from stepping onto a tile
+pub enum Obstacle {
+    Wall,
+    Entity(Entity),
+}
+
+/// The tile-blocking rule for anything moving around the map: walls stop it,
+/// and so does anything that can take a hit.
+pub fn obstacle<P, H>(
+    map: &Map,
+    entities: &Entities,
+    pos: &Storage<Position, P>,
+    hp: &Storage<HitPoints, H>,
+    mover: Entity,
+    (x, y): (i32, i32),
+) -> Option<Obstacle>
+where
+    P: Deref<Target = MaskedStorage<Position>>,
+    H: Deref<Target = MaskedStorage<HitPoints>>,
+{
+    if map.blocked(x, y) {
+        return Some(Obstacle::Wall);
+    }
+
+    (entities, pos, hp)
+        .join()
+        .find(|(e, p, _)| *e != mover && p.x == x && p.y == y)
+        .map(|(e, _, _)| Obstacle::Entity(e))
+}
 
 #[derive(Default)]
 pub struct Movement;
 
 impl<'a> System<'a> for Movement {
     type SystemData = (
+        Entities<'a>,
         WriteStorage<'a, Position>,
-        ReadStorage<'a, Player>,
-        Read<'a, InputMapping>,
+        WriteStorage<'a, WantsToMove>,
+        ReadStorage<'a, HitPoints>,
+        WriteStorage<'a, MechBody>,
+        WriteStorage<'a, Heat>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, MobilityBonus>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Vendor>,
+        ReadStorage<'a, Ai>,
+        WriteStorage<'a, WantsToMelee>,
+        ReadExpect<'a, Map>,
+        Write<'a, MesLogs>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut pos, player, im) = data;
-
-        for (pos, _) in (&mut pos, &player).join() {
-            match &im.key {
-                Some(VirtualKey::MoveUp) => pos.y -= 1,
-                Some(VirtualKey::MoveDown) => pos.y += 1,
-                Some(VirtualKey::MoveLeft) => pos.x -= 1,
-                Some(VirtualKey::MoveRight) => pos.x += 1,
-                _ => {}
+        let (
+            entities,
+            mut pos,
+            mut wants_move,
+            hp,
+            mut bodies,
+            mut heat,
+            equipped,
+            mobility,
+            names,
+            vendors,
+            ai,
+            mut wants_melee,
+            map,
+            mut logs,
+        ) = data;
+
+        let moves: Vec<_> = (&entities, &wants_move)
+            .join()
+            .map(|(e, m)| (e, m.dx, m.dy, m.sprint))
+            .collect();
+        wants_move.clear();
+
+        for (mover, dx, dy, sprint) in moves {
+            let mut steps = 1;
+
+            if let Some(body) = bodies.get_mut(mover) {
+                if !body.operational(PartKind::Legs) {
+                    body.staggered = !body.staggered;
+                    if body.staggered {
+                        logs.add_message(format!(
+                            "{} drags its wrecked legs.",
+                            Name::of(&names, mover)
+                        ));
+                        continue;
+                    }
+                } else if sprint {
+                    steps = 2;
+                }
+            }
+
+            if steps > 1 {
+                let saved =
+                    Equipped::bonus(&equipped, &mobility, &bodies, mover, |b| b.sprint_heat);
+                if let Some(heat) = heat.get_mut(mover) {
+                    heat.add((SPRINT_HEAT - saved).max(0));
+                }
+            }
+
+            for _ in 0..steps {
+                let (tx, ty) = match pos.get(mover) {
+                    Some(p) => (p.x + dx, p.y + dy),
+                    None => break,
+                };
+
+                // bump into anything that can take a hit, otherwise just walk
+                match obstacle(&map, &entities, &pos, &hp, mover, (tx, ty)) {
+                    Some(Obstacle::Wall) => break,
+                    // nobody picks a fight with the traders by walking into them
+                    Some(Obstacle::Entity(target)) if vendors.contains(target) => break,
+                    // and monsters don't turn on each other
+                    Some(Obstacle::Entity(target)) if ai.contains(mover) && ai.contains(target) => {
+                        break
+                    }
+                    Some(Obstacle::Entity(target)) => {
+                        let _ = wants_melee.insert(mover, WantsToMelee { target });
+                        break;
+                    }
+                    None => {}
+                }
+
+                if let Some(p) = pos.get_mut(mover) {
+                    p.x = tx;
+                    p.y = ty;
+                }
             }
         }
     }
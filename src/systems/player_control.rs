@@ -0,0 +1,351 @@
+use std::ops::Deref;
+
+use specs::storage::MaskedStorage;
+use specs::{
+    join::Join, Entities, Entity, Read, ReadExpect, ReadStorage, Storage, System, Write,
+    WriteStorage,
+};
+
+use crate::components::{
+    Ai, Ammo, AutoExplore, EffectKind, Heat, HitPoints, InBackpack, Item, MechBody, Name, Player,
+    Position, ProvidesRepair, RangedWeapon, Reloading, Salvageable, Stackable, StatusEffects,
+    Travel, Viewshed, WantsToMove, WantsToPickUp, WantsToSalvage, WantsToUseItem,
+};
+use crate::consts::BACKPACK_CAPACITY;
+use crate::map::Map;
+use crate::pathfinding::DijkstraMap;
+use crate::resources::{GameMode, InputMapping, MesLogs, PlayerTurn, VirtualKey};
+
+#[derive(Default)]
+pub struct PlayerControl;
+
+enum Action {
+    Move(i32, i32, bool),
+    Wait,
+    Reload,
+    Repair,
+    PickUp,
+    Salvage,
+    Travel,
+    Explore,
+}
+
+impl<'a> System<'a> for PlayerControl {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, HitPoints>,
+        ReadStorage<'a, Heat>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, RangedWeapon>,
+        ReadStorage<'a, MechBody>,
+        ReadStorage<'a, Ammo>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Stackable>,
+        ReadStorage<'a, Item>,
+        WriteStorage<'a, Reloading>,
+        ReadStorage<'a, ProvidesRepair>,
+        ReadStorage<'a, StatusEffects>,
+        WriteStorage<'a, WantsToMove>,
+        WriteStorage<'a, WantsToUseItem>,
+        WriteStorage<'a, WantsToPickUp>,
+        ReadStorage<'a, Salvageable>,
+        WriteStorage<'a, WantsToSalvage>,
+        // walking somewhere without a key for every step
+        (
+            WriteStorage<'a, Travel>,
+            WriteStorage<'a, AutoExplore>,
+            ReadStorage<'a, Ai>,
+            ReadStorage<'a, Viewshed>,
+            ReadExpect<'a, Map>,
+        ),
+        Read<'a, InputMapping>,
+        Read<'a, GameMode>,
+        Write<'a, PlayerTurn>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player,
+            pos,
+            hp,
+            heat,
+            names,
+            weapons,
+            bodies,
+            ammo,
+            backpack,
+            stacks,
+            items,
+            mut reloading,
+            kits,
+            effects,
+            mut wants_move,
+            mut wants_use,
+            mut wants_pick_up,
+            salvage,
+            mut wants_salvage,
+            (mut travel, mut exploring, ai, viewsheds, map),
+            im,
+            mode,
+            mut turn,
+            mut logs,
+        ) = data;
+
+        turn.0 = false;
+
+        // any key stops the player on their way
+        if im.key.is_some() {
+            travel.clear();
+            exploring.clear();
+        }
+
+        // keys mean something else while aiming
+        if *mode != GameMode::Normal {
+            return;
+        }
+
+        let action = match &im.key {
+            Some(VirtualKey::MoveUp) => Action::Move(0, -1, false),
+            Some(VirtualKey::MoveDown) => Action::Move(0, 1, false),
+            Some(VirtualKey::MoveLeft) => Action::Move(-1, 0, false),
+            Some(VirtualKey::MoveRight) => Action::Move(1, 0, false),
+            Some(VirtualKey::SprintUp) => Action::Move(0, -1, true),
+            Some(VirtualKey::SprintDown) => Action::Move(0, 1, true),
+            Some(VirtualKey::SprintLeft) => Action::Move(-1, 0, true),
+            Some(VirtualKey::SprintRight) => Action::Move(1, 0, true),
+            Some(VirtualKey::Wait) => Action::Wait,
+            Some(VirtualKey::Reload) => Action::Reload,
+            Some(VirtualKey::Repair) => Action::Repair,
+            Some(VirtualKey::PickItem) => Action::PickUp,
+            Some(VirtualKey::Salvage) => Action::Salvage,
+            Some(VirtualKey::Explore) => Action::Explore,
+            None if (&travel).join().next().is_some() || (&exploring).join().next().is_some() => {
+                Action::Travel
+            }
+            _ => return,
+        };
+
+        for (e, _, health) in (&entities, &player, &hp).join() {
+            if !health.alive() {
+                continue;
+            }
+
+            turn.0 = true;
+
+            if let Some(reason) = busy(e, &names, &heat, &effects, &reloading) {
+                logs.add_message(reason);
+                travel.remove(e);
+                exploring.remove(e);
+                continue;
+            }
+
+            match action {
+                Action::Move(dx, dy, sprint) => {
+                    let _ = wants_move.insert(e, WantsToMove { dx, dy, sprint });
+                }
+                Action::Wait => {}
+                Action::Travel | Action::Explore => {
+                    if let Action::Explore = action {
+                        let _ = exploring.insert(e, AutoExplore);
+                    }
+
+                    // stop as soon as anything hostile shows up
+                    let spotted = viewsheds.get(e).and_then(|viewshed| {
+                        (&entities, &ai, &pos, &hp)
+                            .join()
+                            .find(|(_, _, p, hp)| hp.alive() && viewshed.can_see(p.x, p.y))
+                            .map(|(monster, _, _, _)| monster)
+                    });
+                    let at = pos.get(e).map(|p| (p.x, p.y));
+                    let step = if exploring.contains(e) {
+                        // downhill towards the nearest unexplored ground, over known ground
+                        let occupied: Vec<_> =
+                            (&pos, &hp).join().map(|(p, _)| (p.x, p.y)).collect();
+                        at.and_then(|at| {
+                            DijkstraMap::new(&map, &map.frontier(), |(x, y)| {
+                                map.tile(x, y).explored
+                            })
+                            .downhill(at, |next| occupied.contains(&next))
+                        })
+                    } else {
+                        travel.get_mut(e).and_then(|t| t.steps.pop())
+                    };
+
+                    match (spotted, at, step) {
+                        (None, Some((x, y)), Some((sx, sy)))
+                            if (sx - x).abs() <= 1 && (sy - y).abs() <= 1 =>
+                        {
+                            let _ = wants_move.insert(
+                                e,
+                                WantsToMove {
+                                    dx: sx - x,
+                                    dy: sy - y,
+                                    sprint: false,
+                                },
+                            );
+                        }
+                        _ => {
+                            match spotted {
+                                Some(monster) => logs.add_message(format!(
+                                    "You stop, {} is in sight.",
+                                    Name::of(&names, monster)
+                                )),
+                                None if exploring.contains(e) => {
+                                    logs.add_message("There's nothing left to explore.")
+                                }
+                                None => {}
+                            }
+                            travel.remove(e);
+                            exploring.remove(e);
+                            turn.0 = false;
+                        }
+                    }
+                }
+                Action::Reload => {
+                    let weapon = match weapons.get(e) {
+                        Some(weapon) => weapon,
+                        None => {
+                            logs.add_message("You have no ranged weapon.");
+                            turn.0 = false;
+                            continue;
+                        }
+                    };
+
+                    if bodies.get(e).is_some_and(|b| !b.operational(weapon.mount)) {
+                        logs.add_message(format!(
+                            "Your {} went down with your {}.",
+                            weapon.name,
+                            weapon.mount.name()
+                        ));
+                        turn.0 = false;
+                    } else if weapon.loaded >= weapon.magazine {
+                        logs.add_message(format!("Your {} is fully loaded.", weapon.name));
+                        turn.0 = false;
+                    } else if Ammo::carried(&ammo, &stacks, &backpack, e, weapon.ammo_kind) <= 0 {
+                        logs.add_message(format!(
+                            "You have no {} left for your {}.",
+                            weapon.ammo_kind.name(),
+                            weapon.name
+                        ));
+                        turn.0 = false;
+                    } else {
+                        let _ = reloading.insert(
+                            e,
+                            Reloading {
+                                turns_left: weapon.reload_turns,
+                            },
+                        );
+                    }
+                }
+                Action::Repair => {
+                    let kit = (&entities, &kits, &backpack)
+                        .join()
+                        .find(|(_, _, carried)| carried.owner == e)
+                        .map(|(kit, _, _)| kit);
+
+                    let worn = bodies.get(e).is_some_and(MechBody::needs_repair);
+
+                    match kit {
+                        None => {
+                            logs.add_message("You have no repair kits.");
+                            turn.0 = false;
+                        }
+                        Some(_) if !worn => {
+                            logs.add_message("Your armor needs no patching.");
+                            turn.0 = false;
+                        }
+                        Some(kit) => {
+                            let _ = wants_use.insert(
+                                e,
+                                WantsToUseItem {
+                                    item: kit,
+                                    target: None,
+                                },
+                            );
+                        }
+                    }
+                }
+                Action::PickUp => {
+                    let here = pos.get(e).map(|p| (p.x, p.y));
+                    let item = (&entities, &items, &pos)
+                        .join()
+                        .find(|(_, _, p)| Some((p.x, p.y)) == here)
+                        .map(|(item, _, _)| item);
+
+                    match item {
+                        None => {
+                            logs.add_message("There is nothing here to pick up.");
+                            turn.0 = false;
+                        }
+                        // a full backpack still has room on the stacks in it
+                        Some(item)
+                            if InBackpack::count(&backpack, e) >= BACKPACK_CAPACITY
+                                && Stackable::matching(
+                                    &stacks, &backpack, &names, &entities, e, item,
+                                )
+                                .is_empty() =>
+                        {
+                            logs.add_message("Your backpack is full.");
+                            turn.0 = false;
+                        }
+                        Some(item) => {
+                            let _ = wants_pick_up.insert(e, WantsToPickUp { item });
+                        }
+                    }
+                }
+                Action::Salvage => {
+                    // anything underfoot or right next to the player
+                    let here = pos.get(e).map(|p| (p.x, p.y));
+                    let wreck = (&entities, &salvage, &pos)
+                        .join()
+                        .find(|(_, _, p)| {
+                            here.is_some_and(|(x, y)| (p.x - x).abs() <= 1 && (p.y - y).abs() <= 1)
+                        })
+                        .map(|(wreck, _, _)| wreck);
+
+                    match wreck {
+                        Some(wreck) => {
+                            let _ = wants_salvage.insert(e, WantsToSalvage { wreck });
+                        }
+                        None => {
+                            logs.add_message("There is nothing here to salvage.");
+                            turn.0 = false;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Why `e` has to sit this turn out, whatever the player asked for
+pub fn busy<R>(
+    e: Entity,
+    names: &ReadStorage<Name>,
+    heat: &ReadStorage<Heat>,
+    effects: &ReadStorage<StatusEffects>,
+    reloading: &Storage<Reloading, R>,
+) -> Option<String>
+where
+    R: Deref<Target = MaskedStorage<Reloading>>,
+{
+    // a shut down mech can only wait for its reactor to come back
+    if heat.get(e).is_some_and(Heat::shut_down) {
+        return Some(format!("{} is shut down.", Name::of(names, e)));
+    }
+
+    if effects.get(e).is_some_and(|s| s.has(EffectKind::Stunned)) {
+        return Some(format!("{} is stunned.", Name::of(names, e)));
+    }
+
+    // same goes for a mech busy reloading
+    if reloading.get(e).is_some() {
+        return Some(format!("{} keeps reloading.", Name::of(names, e)));
+    }
+
+    None
+}
@@ -0,0 +1,258 @@
+use rand::Rng;
+use specs::{join::Join, Entities, ReadExpect, ReadStorage, System, Write, WriteStorage};
+
+use crate::components::{
+    AccuracyBonus, Attack, Blast, DefenseBonus, EffectKind, Equipped, Heat, Hit, HitPoints,
+    Knockback, MechBody, Name, Position, RangedWeapon, StatusEffects, SufferDamage, WantsToShoot,
+};
+use crate::map::{direction, distance, AreaShape, Map};
+use crate::resources::{AttackRecord, CombatLog, HitOutcome, MesLogs};
+
+#[derive(Default)]
+pub struct RangedCombat;
+
+impl<'a> System<'a> for RangedCombat {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, WantsToShoot>,
+        WriteStorage<'a, RangedWeapon>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, HitPoints>,
+        ReadStorage<'a, MechBody>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, AccuracyBonus>,
+        ReadStorage<'a, DefenseBonus>,
+        WriteStorage<'a, Heat>,
+        ReadStorage<'a, StatusEffects>,
+        ReadStorage<'a, Name>,
+        WriteStorage<'a, SufferDamage>,
+        WriteStorage<'a, Blast>,
+        WriteStorage<'a, Knockback>,
+        ReadExpect<'a, Map>,
+        Write<'a, MesLogs>,
+        Write<'a, CombatLog>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut wants_shoot,
+            mut weapons,
+            pos,
+            hp,
+            bodies,
+            equipped,
+            accuracy_bonus,
+            defense_bonus,
+            mut heat,
+            effects,
+            names,
+            mut suffer,
+            mut blasts,
+            mut knockback,
+            map,
+            mut logs,
+            mut combat_log,
+        ) = data;
+
+        for (shooter, wants, weapon, from) in (&entities, &wants_shoot, &mut weapons, &pos).join() {
+            if weapon.loaded <= 0
+                || bodies
+                    .get(shooter)
+                    .is_some_and(|b| !b.operational(weapon.mount))
+                || effects.get(shooter).is_some_and(|s| s.has(EffectKind::Emp))
+            {
+                continue;
+            }
+
+            weapon.loaded -= 1;
+            let mut penalty = 0;
+            if let Some(heat) = heat.get_mut(shooter) {
+                penalty = heat.accuracy_penalty();
+                heat.add(weapon.heat);
+            }
+
+            let bonus =
+                Equipped::bonus(&equipped, &accuracy_bonus, &bodies, shooter, |b| b.accuracy);
+            let origin = (from.x, from.y);
+
+            // cones spread straight out of the muzzle, nothing to aim
+            if let Some(warhead) = weapon.warhead {
+                if let AreaShape::Cone { .. } = warhead.shape {
+                    logs.add_message(format!(
+                        "{} fires its {}.",
+                        Name::of(&names, shooter),
+                        weapon.name
+                    ));
+                    let _ = blasts.insert(
+                        entities.create(),
+                        Blast {
+                            center: origin,
+                            toward: wants.target,
+                            shape: warhead.shape,
+                            damage: weapon.damage,
+                            damage_type: weapon.damage_type,
+                            effect: weapon.on_hit,
+                            knockback: weapon.knockback,
+                            source: shooter,
+                            attack: Attack::new(&weapon.name),
+                        },
+                    );
+                    continue;
+                }
+            }
+
+            let path = map.line_of_fire(origin, wants.target, weapon.range);
+
+            // the shot hits the first thing standing in its way
+            let victim = path.iter().find_map(|&(x, y)| {
+                (&entities, &pos, &hp)
+                    .join()
+                    .find(|(e, p, hp)| *e != shooter && hp.alive() && (p.x, p.y) == (x, y))
+                    .map(|(e, p, _)| (e, (p.x, p.y)))
+            });
+
+            // roll against the first thing in the line of fire, if anything
+            let shot = victim.map(|(_, at)| {
+                let range = distance(origin, at);
+                Attack::new(&weapon.name)
+                    .rolled(
+                        rand::thread_rng().gen_range(0, 100),
+                        weapon.hit_chance(range, penalty - bonus),
+                    )
+                    .modifier("accuracy", weapon.accuracy)
+                    .modifier("range", -(range * 3.0) as i32)
+                    .modifier("heat", -penalty)
+                    .modifier("equipment", bonus)
+            });
+
+            if let Some(warhead) = weapon.warhead {
+                // a hit lands right on the victim, anything else wherever the shot ends up
+                let on_target = shot.as_ref().is_some_and(Attack::hits);
+                let impact = match victim {
+                    Some((_, at)) if on_target => at,
+                    _ => map.landing(origin, wants.target, weapon.range),
+                };
+
+                logs.add_message(format!(
+                    "{} fires its {}.",
+                    Name::of(&names, shooter),
+                    weapon.name
+                ));
+                for _ in 0..warhead.count {
+                    let center = if on_target && warhead.count == 1 {
+                        impact
+                    } else {
+                        scatter(&map, impact)
+                    };
+                    let _ = blasts.insert(
+                        entities.create(),
+                        Blast {
+                            center,
+                            toward: center,
+                            shape: warhead.shape,
+                            damage: weapon.damage,
+                            damage_type: weapon.damage_type,
+                            effect: weapon.on_hit,
+                            knockback: weapon.knockback,
+                            source: shooter,
+                            attack: shot.clone().unwrap_or_else(|| Attack::new(&weapon.name)),
+                        },
+                    );
+                }
+                continue;
+            }
+
+            let ((victim, at), shot) = match (victim, shot) {
+                (Some(found), Some(shot)) => (found, shot),
+                _ => {
+                    logs.add_message(format!(
+                        "{} fires its {} and hits nothing.",
+                        Name::of(&names, shooter),
+                        weapon.name
+                    ));
+                    continue;
+                }
+            };
+
+            if shot.hits() {
+                let defense =
+                    Equipped::bonus(&equipped, &defense_bonus, &bodies, victim, |b| b.defense);
+                let amount = weapon.damage - defense;
+                let shot = shot.modifier("defense", -defense);
+                if amount > 0 {
+                    SufferDamage::add_hit(
+                        &mut suffer,
+                        victim,
+                        Hit {
+                            source: shooter,
+                            amount,
+                            damage_type: weapon.damage_type,
+                            effect: weapon.on_hit,
+                            attack: shot,
+                        },
+                    );
+                } else {
+                    logs.add_message(format!(
+                        "{}'s {} glances off {}.",
+                        Name::of(&names, shooter),
+                        weapon.name,
+                        Name::of(&names, victim)
+                    ));
+                    combat_log.record(AttackRecord {
+                        attacker: Name::of(&names, shooter),
+                        target: Name::of(&names, victim),
+                        attack: shot,
+                        outcome: Some(HitOutcome {
+                            base: amount,
+                            damage_type: weapon.damage_type.name(),
+                            multiplier: 1.0,
+                            location: None,
+                            absorbed: 0,
+                            damage: 0,
+                        }),
+                    });
+                }
+
+                if weapon.knockback > 0 {
+                    let (dx, dy) = direction(origin, at);
+                    let _ = knockback.insert(
+                        victim,
+                        Knockback {
+                            dx,
+                            dy,
+                            force: weapon.knockback,
+                        },
+                    );
+                }
+            } else {
+                logs.add_message(format!(
+                    "{}'s {} misses {}.",
+                    Name::of(&names, shooter),
+                    weapon.name,
+                    Name::of(&names, victim)
+                ));
+                combat_log.record(AttackRecord {
+                    attacker: Name::of(&names, shooter),
+                    target: Name::of(&names, victim),
+                    attack: shot,
+                    outcome: None,
+                });
+            }
+        }
+
+        wants_shoot.clear();
+    }
+}
+
+/// Somewhere next to `at` that isn't inside a wall
+fn scatter(map: &Map, at: (i32, i32)) -> (i32, i32) {
+    let x = at.0 + rand::thread_rng().gen_range(-1, 2);
+    let y = at.1 + rand::thread_rng().gen_range(-1, 2);
+
+    if map.blocked(x, y) {
+        at
+    } else {
+        (x, y)
+    }
+}
@@ -0,0 +1,79 @@
+use specs::{join::Join, Entities, Read, ReadStorage, System, Write, WriteStorage};
+
+use crate::components::{Ammo, InBackpack, Name, RangedWeapon, Reloading, Stackable};
+use crate::resources::{MesLogs, PlayerTurn};
+
+#[derive(Default)]
+pub struct Reload;
+
+impl<'a> System<'a> for Reload {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Reloading>,
+        WriteStorage<'a, RangedWeapon>,
+        ReadStorage<'a, Ammo>,
+        WriteStorage<'a, Stackable>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Name>,
+        Read<'a, PlayerTurn>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut reloading,
+            mut weapons,
+            ammo,
+            mut stacks,
+            backpack,
+            names,
+            turn,
+            mut logs,
+        ) = data;
+
+        if !turn.0 {
+            return;
+        }
+
+        let mut done = vec![];
+
+        for (e, reload, weapon) in (&entities, &mut reloading, &mut weapons).join() {
+            reload.turns_left -= 1;
+            if reload.turns_left > 0 {
+                continue;
+            }
+            done.push(e);
+
+            // fill the magazine from whatever matching ammo is carried
+            for (ammo_entity, ammo, stack, carried) in
+                (&entities, &ammo, &mut stacks, &backpack).join()
+            {
+                if carried.owner != e || ammo.kind != weapon.ammo_kind {
+                    continue;
+                }
+
+                let taken = (weapon.magazine - weapon.loaded).min(stack.quantity);
+                weapon.loaded += taken;
+                if stack.take(taken) {
+                    let _ = entities.delete(ammo_entity);
+                }
+                if weapon.loaded >= weapon.magazine {
+                    break;
+                }
+            }
+
+            logs.add_message(format!(
+                "{} reloads its {} ({}/{}).",
+                Name::of(&names, e),
+                weapon.name,
+                weapon.loaded,
+                weapon.magazine
+            ));
+        }
+
+        for e in done {
+            reloading.remove(e);
+        }
+    }
+}
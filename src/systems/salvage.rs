@@ -0,0 +1,71 @@
+use specs::{join::Join, Entities, LazyUpdate, Read, ReadStorage, System, Write, WriteStorage};
+
+use crate::components::{Name, Position, Salvageable, WantsToSalvage};
+use crate::resources::MesLogs;
+use crate::spawner;
+
+#[derive(Default)]
+pub struct Salvage;
+
+impl<'a> System<'a> for Salvage {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, WantsToSalvage>,
+        WriteStorage<'a, Salvageable>,
+        WriteStorage<'a, Name>,
+        ReadStorage<'a, Position>,
+        Read<'a, LazyUpdate>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut wants_salvage, mut salvage, mut names, pos, lazy, mut logs) = data;
+
+        for (e, wants) in (&entities, &wants_salvage).join() {
+            let wreck = wants.wreck;
+            let (x, y) = match pos.get(wreck) {
+                Some(p) => (p.x, p.y),
+                None => continue,
+            };
+            let found = match salvage.remove(wreck) {
+                Some(found) => found,
+                None => continue,
+            };
+
+            // everything comes out in a heap on top of the wreck, to be picked up
+            let mut pile = vec![(found.scrap, "scrap")];
+            for part in found.parts {
+                match pile.iter_mut().find(|(_, name)| *name == part) {
+                    Some((quantity, _)) => *quantity += 1,
+                    None => pile.push((1, part)),
+                }
+            }
+
+            let listed: Vec<_> = pile
+                .iter()
+                .map(|(quantity, name)| format!("{} {}", quantity, name))
+                .collect();
+            logs.add_message(format!(
+                "{} strips the {} for {}.",
+                Name::of(&names, e),
+                Name::of(&names, wreck),
+                listed.join(", ")
+            ));
+
+            lazy.exec_mut(move |world| {
+                for (quantity, name) in pile {
+                    if let Some(item) = spawner::item_stack(world, name, quantity) {
+                        let _ = world
+                            .write_storage::<Position>()
+                            .insert(item, Position { x, y });
+                    }
+                }
+            });
+
+            let stripped = format!("stripped {}", Name::of(&names, wreck));
+            let _ = names.insert(wreck, Name(stripped));
+        }
+
+        wants_salvage.clear();
+    }
+}
@@ -0,0 +1,200 @@
+use std::ops::Deref;
+
+use specs::storage::MaskedStorage;
+use specs::{
+    join::Join, Entities, Entity, Read, ReadStorage, Storage, System, Write, WriteStorage,
+};
+
+use super::inventory::letter_index;
+use super::player_control::busy;
+use crate::components::{
+    Heat, HitPoints, InBackpack, Item, Name, Player, Position, Reloading, Stackable, StatusEffects,
+    Vendor, Wallet, WantsToBuy, WantsToSell,
+};
+use crate::consts::{BACKPACK_CAPACITY, SELL_PERCENT};
+use crate::map::distance;
+use crate::resources::{GameMode, InputMapping, MesLogs, PlayerTurn, VirtualKey};
+
+#[derive(Default)]
+pub struct ShopMenu;
+
+impl<'a> System<'a> for ShopMenu {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, HitPoints>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Vendor>,
+        ReadStorage<'a, Wallet>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Stackable>,
+        ReadStorage<'a, Heat>,
+        ReadStorage<'a, StatusEffects>,
+        ReadStorage<'a, Reloading>,
+        WriteStorage<'a, WantsToBuy>,
+        WriteStorage<'a, WantsToSell>,
+        Read<'a, InputMapping>,
+        Write<'a, GameMode>,
+        Write<'a, PlayerTurn>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player,
+            hp,
+            names,
+            pos,
+            vendors,
+            wallets,
+            items,
+            backpack,
+            stacks,
+            heat,
+            effects,
+            reloading,
+            mut wants_buy,
+            mut wants_sell,
+            im,
+            mut mode,
+            mut turn,
+            mut logs,
+        ) = data;
+
+        let (player_entity, player_pos) = match (&entities, &pos, &player).join().next() {
+            Some((e, p, _)) => (e, (p.x, p.y)),
+            None => return,
+        };
+
+        let letter = match (*mode, &im.key) {
+            (GameMode::Normal, Some(VirtualKey::Trade)) => {
+                // vendors only deal with whoever is right next to them
+                let vendor = (&entities, &vendors, &pos)
+                    .join()
+                    .find(|(_, _, p)| distance((p.x, p.y), player_pos) < 2.0)
+                    .map(|(e, _, _)| e);
+                match vendor {
+                    Some(vendor) => {
+                        *mode = GameMode::Shop {
+                            vendor,
+                            selling: false,
+                        }
+                    }
+                    None => logs.add_message("There's nobody here to trade with."),
+                }
+                return;
+            }
+            (GameMode::Shop { .. }, Some(VirtualKey::Cancel)) => {
+                *mode = GameMode::Normal;
+                return;
+            }
+            (GameMode::Shop { vendor, selling }, Some(VirtualKey::NextTarget)) => {
+                *mode = GameMode::Shop {
+                    vendor,
+                    selling: !selling,
+                };
+                return;
+            }
+            (GameMode::Shop { .. }, Some(VirtualKey::Letter(letter))) => *letter,
+            _ => return,
+        };
+
+        let (vendor, selling) = match *mode {
+            GameMode::Shop { vendor, selling } => (vendor, selling),
+            _ => return,
+        };
+
+        // the vendor may not have made it
+        if !hp.get(vendor).is_some_and(HitPoints::alive) {
+            *mode = GameMode::Normal;
+            return;
+        }
+
+        // the same order the shop screen lists them in
+        let owner = if selling { player_entity } else { vendor };
+        let item = match letter_index(letter).and_then(|idx| {
+            listed(&entities, &items, &backpack, owner)
+                .get(idx)
+                .copied()
+        }) {
+            Some(item) => item,
+            None => return,
+        };
+
+        if !hp.get(player_entity).is_some_and(HitPoints::alive) {
+            return;
+        }
+        if let Some(reason) = busy(player_entity, &names, &heat, &effects, &reloading) {
+            logs.add_message(reason);
+            turn.0 = true;
+            return;
+        }
+
+        let worth = price(&items, &stacks, item);
+        let vendor_name = Name::of(&names, vendor);
+        let item_name = Name::of(&names, item);
+
+        if selling {
+            let offer = offer(worth);
+            if wallets.get(vendor).is_none_or(|w| w.credits < offer) {
+                logs.add_message(format!(
+                    "The {} can't afford the {}.",
+                    vendor_name, item_name
+                ));
+                return;
+            }
+            let _ = wants_sell.insert(player_entity, WantsToSell { vendor, item });
+        } else {
+            if wallets.get(player_entity).is_none_or(|w| w.credits < worth) {
+                logs.add_message(format!(
+                    "You can't afford the {} ({} credits).",
+                    item_name, worth
+                ));
+                return;
+            }
+            if InBackpack::count(&backpack, player_entity) >= BACKPACK_CAPACITY {
+                logs.add_message("Your backpack is full.");
+                return;
+            }
+            let _ = wants_buy.insert(player_entity, WantsToBuy { vendor, item });
+        }
+
+        turn.0 = true;
+    }
+}
+
+/// Items carried by `owner`, in the order the shop screen lists them
+pub fn listed<I, B>(
+    entities: &Entities,
+    items: &Storage<Item, I>,
+    backpack: &Storage<InBackpack, B>,
+    owner: Entity,
+) -> Vec<Entity>
+where
+    I: Deref<Target = MaskedStorage<Item>>,
+    B: Deref<Target = MaskedStorage<InBackpack>>,
+{
+    (entities, items, backpack)
+        .join()
+        .filter(|(_, _, b)| b.owner == owner)
+        .map(|(e, _, _)| e)
+        .collect()
+}
+
+/// What a vendor asks for the whole stack of `item`
+pub fn price<I, S>(items: &Storage<Item, I>, stacks: &Storage<Stackable, S>, item: Entity) -> i32
+where
+    I: Deref<Target = MaskedStorage<Item>>,
+    S: Deref<Target = MaskedStorage<Stackable>>,
+{
+    let quantity = stacks.get(item).map_or(1, |s| s.quantity);
+    items.get(item).map_or(0, |i| i.value * quantity)
+}
+
+/// What a vendor pays for something it would sell for `price`
+pub fn offer(price: i32) -> i32 {
+    price * SELL_PERCENT / 100
+}
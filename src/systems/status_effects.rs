@@ -0,0 +1,78 @@
+use specs::{join::Join, Entities, Read, ReadStorage, System, Write, WriteStorage};
+
+use crate::components::{
+    Attack, DamageType, EffectKind, Heat, Hit, HitPoints, Name, StatusEffects, SufferDamage,
+};
+use crate::consts::{BURN_DAMAGE, BURN_HEAT, CORRODE_DAMAGE};
+use crate::resources::{MesLogs, PlayerTurn};
+
+#[derive(Default)]
+pub struct StatusTick;
+
+impl<'a> System<'a> for StatusTick {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, StatusEffects>,
+        ReadStorage<'a, HitPoints>,
+        WriteStorage<'a, Heat>,
+        WriteStorage<'a, SufferDamage>,
+        ReadStorage<'a, Name>,
+        Read<'a, PlayerTurn>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut effects, hp, mut heat, mut suffer, names, turn, mut logs) = data;
+
+        if !turn.0 {
+            return;
+        }
+
+        for (e, effects, hp) in (&entities, &mut effects, &hp).join() {
+            if !hp.alive() {
+                continue;
+            }
+
+            for effect in &mut effects.effects {
+                let damage = match effect.kind {
+                    EffectKind::Burning => {
+                        if let Some(heat) = heat.get_mut(e) {
+                            heat.add(BURN_HEAT * effect.stacks);
+                        }
+                        Some((BURN_DAMAGE * effect.stacks, DamageType::Thermal))
+                    }
+                    EffectKind::Corroding => {
+                        Some((CORRODE_DAMAGE * effect.stacks, DamageType::Corrosive))
+                    }
+                    // these only get in the way of what the entity does
+                    EffectKind::Stunned | EffectKind::Emp => None,
+                };
+
+                if let Some((amount, damage_type)) = damage {
+                    SufferDamage::add_hit(
+                        &mut suffer,
+                        e,
+                        Hit {
+                            source: e,
+                            amount,
+                            damage_type,
+                            effect: None,
+                            attack: Attack::new(effect.kind.name()),
+                        },
+                    );
+                }
+
+                effect.turns -= 1;
+                if effect.turns <= 0 {
+                    logs.add_message(format!(
+                        "{} is no longer {}.",
+                        Name::of(&names, e),
+                        effect.kind.name()
+                    ));
+                }
+            }
+
+            effects.effects.retain(|effect| effect.turns > 0);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use specs::{
+    join::Join, Entities, Entity, Read, ReadExpect, ReadStorage, System, Write, WriteStorage,
+};
+
+use super::player_control::busy;
+use crate::components::{
+    EffectKind, Heat, HitPoints, MechBody, Name, Player, Position, RangedWeapon, Reloading,
+    StatusEffects, Viewshed, WantsToShoot, WantsToUseItem,
+};
+use crate::map::{distance, Map};
+use crate::resources::{GameMode, InputMapping, MesLogs, PlayerTurn, VirtualKey};
+
+#[derive(Default)]
+pub struct Targeting;
+
+impl<'a> System<'a> for Targeting {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Viewshed>,
+        ReadStorage<'a, HitPoints>,
+        ReadStorage<'a, RangedWeapon>,
+        ReadStorage<'a, MechBody>,
+        ReadStorage<'a, Heat>,
+        ReadStorage<'a, StatusEffects>,
+        ReadStorage<'a, Reloading>,
+        WriteStorage<'a, WantsToShoot>,
+        WriteStorage<'a, WantsToUseItem>,
+        ReadExpect<'a, Map>,
+        Read<'a, InputMapping>,
+        Write<'a, GameMode>,
+        Write<'a, PlayerTurn>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player,
+            names,
+            pos,
+            viewsheds,
+            hp,
+            weapons,
+            bodies,
+            heat,
+            effects,
+            reloading,
+            mut wants_shoot,
+            mut wants_use,
+            map,
+            im,
+            mut mode,
+            mut turn,
+            mut logs,
+        ) = data;
+
+        let (player_entity, player_pos, viewshed, _) =
+            match (&entities, &pos, &viewsheds, &player).join().next() {
+                Some(found) => found,
+                None => return,
+            };
+        let origin = (player_pos.x, player_pos.y);
+
+        // hostiles the player can see, closest first
+        let mut hostiles: Vec<_> = (&entities, &pos, &hp)
+            .join()
+            .filter(|(e, p, hp)| *e != player_entity && hp.alive() && viewshed.can_see(p.x, p.y))
+            .map(|(_, p, _)| (p.x, p.y))
+            .collect();
+        hostiles.sort_by(|a, b| {
+            distance(origin, *a)
+                .partial_cmp(&distance(origin, *b))
+                .unwrap()
+        });
+
+        match (*mode, &im.key) {
+            (GameMode::Normal, Some(VirtualKey::Fire)) => {
+                // the turn goes by all the same
+                if let Some(reason) = busy(player_entity, &names, &heat, &effects, &reloading) {
+                    logs.add_message(reason);
+                    turn.0 = true;
+                    return;
+                }
+
+                if let Some(problem) = cannot_fire(player_entity, &weapons, &bodies, &effects, &hp)
+                {
+                    logs.add_message(problem);
+                    return;
+                }
+
+                let (x, y) = hostiles.first().cloned().unwrap_or(origin);
+                *mode = GameMode::Targeting { x, y, item: None };
+            }
+            (GameMode::Targeting { x, y, item }, Some(key)) => {
+                let (dx, dy) = match key {
+                    VirtualKey::MoveUp => (0, -1),
+                    VirtualKey::MoveDown => (0, 1),
+                    VirtualKey::MoveLeft => (-1, 0),
+                    VirtualKey::MoveRight => (1, 0),
+                    _ => (0, 0),
+                };
+                if (dx, dy) != (0, 0) && map.in_bounds(x + dx, y + dy) {
+                    *mode = GameMode::Targeting {
+                        x: x + dx,
+                        y: y + dy,
+                        item,
+                    };
+                }
+
+                match key {
+                    VirtualKey::NextTarget if !hostiles.is_empty() => {
+                        let next = hostiles
+                            .iter()
+                            .position(|&h| h == (x, y))
+                            .map_or(0, |idx| (idx + 1) % hostiles.len());
+                        let (x, y) = hostiles[next];
+                        *mode = GameMode::Targeting { x, y, item };
+                    }
+                    VirtualKey::Confirm if (x, y) != origin => {
+                        if let Some(reason) =
+                            busy(player_entity, &names, &heat, &effects, &reloading)
+                        {
+                            logs.add_message(reason);
+                            turn.0 = true;
+                            *mode = GameMode::Normal;
+                            return;
+                        }
+
+                        match item {
+                            Some(item) => {
+                                let _ = wants_use.insert(
+                                    player_entity,
+                                    WantsToUseItem {
+                                        item,
+                                        target: Some((x, y)),
+                                    },
+                                );
+                            }
+                            None => {
+                                // things may have changed since the gun came up
+                                if let Some(problem) =
+                                    cannot_fire(player_entity, &weapons, &bodies, &effects, &hp)
+                                {
+                                    logs.add_message(problem);
+                                    *mode = GameMode::Normal;
+                                    return;
+                                }
+                                let _ = wants_shoot
+                                    .insert(player_entity, WantsToShoot { target: (x, y) });
+                            }
+                        }
+                        turn.0 = true;
+                        *mode = GameMode::Normal;
+                    }
+                    VirtualKey::Cancel => {
+                        *mode = GameMode::Normal;
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn cannot_fire(
+    e: Entity,
+    weapons: &ReadStorage<RangedWeapon>,
+    bodies: &ReadStorage<MechBody>,
+    effects: &ReadStorage<StatusEffects>,
+    hp: &ReadStorage<HitPoints>,
+) -> Option<String> {
+    if !hp.get(e).is_some_and(HitPoints::alive) {
+        return Some("You are dead.".to_string());
+    }
+
+    let weapon = match weapons.get(e) {
+        Some(weapon) => weapon,
+        None => return Some("You have no ranged weapon.".to_string()),
+    };
+
+    if bodies.get(e).is_some_and(|b| !b.operational(weapon.mount)) {
+        return Some(format!(
+            "Your {} went down with your {}.",
+            weapon.name,
+            weapon.mount.name()
+        ));
+    }
+
+    if weapon.loaded <= 0 {
+        return Some(format!("Your {} is empty, reload it first.", weapon.name));
+    }
+
+    if effects.get(e).is_some_and(|s| s.has(EffectKind::Emp)) {
+        return Some("Your weapons are scrambled by EMP.".to_string());
+    }
+
+    None
+}
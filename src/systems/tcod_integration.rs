@@ -1,13 +1,16 @@
-use specs::{join::Join, ReadStorage, Resources, System};
+use specs::{join::Join, Entities, Read, ReadExpect, ReadStorage, Resources, System, Write};
 
 use tcod::colors;
 use tcod::console::*;
 
-use tcod::map::{FovAlgorithm, Map as FovMap};
 use tcod::Color;
 
+use super::crafting::missing;
+use super::shop::{listed, offer, price};
 use crate::components::*;
 use crate::consts::*;
+use crate::map::{distance, Map};
+use crate::resources::{CombatLog, Flashes, GameMode, MesLogs, Recipes};
 
 #[derive(Default)]
 pub struct TcodIntegration {
@@ -18,24 +21,213 @@ struct Tcod {
     root: Root,
     map: Offscreen,
     panel: Offscreen,
-    fov: FovMap,
 }
 
 impl<'a> System<'a> for TcodIntegration {
-    type SystemData = (ReadStorage<'a, Position>, ReadStorage<'a, MapRenderable>);
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, MapRenderable>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, HitPoints>,
+        ReadStorage<'a, MechBody>,
+        ReadStorage<'a, Heat>,
+        ReadStorage<'a, Viewshed>,
+        ReadStorage<'a, RangedWeapon>,
+        ReadStorage<'a, Ammo>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Stackable>,
+        ReadStorage<'a, Reloading>,
+        ReadStorage<'a, StatusEffects>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, Targeted>,
+        ReadStorage<'a, AreaOfEffect>,
+        ReadStorage<'a, Wallet>,
+        // what the loadout screen shows
+        (
+            ReadStorage<'a, Hardpoints>,
+            ReadStorage<'a, Equipped>,
+            ReadStorage<'a, MeleeBonus>,
+            ReadStorage<'a, DefenseBonus>,
+            ReadStorage<'a, AccuracyBonus>,
+            ReadStorage<'a, MobilityBonus>,
+        ),
+        ReadExpect<'a, Map>,
+        Read<'a, GameMode>,
+        Write<'a, Flashes>,
+        Read<'a, MesLogs>,
+        Read<'a, CombatLog>,
+        Read<'a, Recipes>,
+    );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (pos, mapr) = data;
+        let (
+            entities,
+            pos,
+            mapr,
+            player,
+            hp,
+            bodies,
+            heat,
+            viewsheds,
+            weapons,
+            ammo,
+            backpack,
+            stacks,
+            reloading,
+            effects,
+            names,
+            items,
+            targeted,
+            areas,
+            wallets,
+            (hardpoints, equipped, melee_bonus, defense_bonus, accuracy_bonus, mobility_bonus),
+            map,
+            mode,
+            mut flashes,
+            logs,
+            combat_log,
+            recipes,
+        ) = data;
+
+        let (player_entity, player_pos, viewshed, _) =
+            match (&entities, &pos, &viewsheds, &player).join().next() {
+                Some(found) => found,
+                None => return,
+            };
+
+        if let Some(t) = self.tcod.as_mut() {
+            // go through all tiles, and set their background color
+            for y in 0..MAP_HEIGHT {
+                for x in 0..MAP_WIDTH {
+                    let visible = viewshed.can_see(x, y);
+                    let tile = map.tile(x, y);
+
+                    let color = match (visible, tile.block_sight) {
+                        // outside of field of view:
+                        (false, true) => COLOR_DARK_WALL,
+                        (false, false) => COLOR_DARK_GROUND,
+                        // inside fov:
+                        (true, true) => COLOR_LIGHT_WALL,
+                        (true, false) => COLOR_LIGHT_GROUND,
+                    };
 
-        self.tcod.as_mut().map(|t| {
-            t.map.set_default_foreground(colors::WHITE);
+                    // show explored tiles only (any visible tile is explored already)
+                    let color = if tile.explored { color } else { colors::BLACK };
+                    t.map.set_char_background(x, y, color, BackgroundFlag::Set);
+                }
+            }
+
+            // light up whatever just blew up
+            for &(x, y) in &flashes.tiles {
+                t.map
+                    .set_char_background(x, y, colors::FLAME, BackgroundFlag::Set);
+            }
+            flashes.frames -= 1;
+            if flashes.frames <= 0 {
+                flashes.tiles.clear();
+            }
+
+            // line of fire preview, the reticle goes on top
+            let mut aim = None;
+            if let GameMode::Targeting { x, y, item } = *mode {
+                let origin = (player_pos.x, player_pos.y);
+                if let Some(item) = item {
+                    let range = targeted.get(item).map_or(0, |t| t.range);
+
+                    // how far it can be thrown, what it flies over and what it catches
+                    for &(px, py) in &viewshed.visible {
+                        if distance(origin, (px, py)) <= range as f32 {
+                            t.map.set_char_background(
+                                px,
+                                py,
+                                colors::DARKEST_YELLOW,
+                                BackgroundFlag::Set,
+                            );
+                        }
+                    }
+                    for (px, py) in map.line_of_fire(origin, (x, y), range) {
+                        t.map
+                            .set_char_background(px, py, colors::DARK_YELLOW, BackgroundFlag::Set);
+                    }
+                    let at = map.landing(origin, (x, y), range);
+                    let area = areas
+                        .get(item)
+                        .map_or(vec![at], |a| map.area(a.shape, at, at));
+                    for (px, py) in area {
+                        t.map
+                            .set_char_background(px, py, colors::DARK_ORANGE, BackgroundFlag::Set);
+                    }
+
+                    aim = Some(format!("Throw: {}", Name::of(&names, item)));
+                } else if let Some(weapon) = weapons.get(player_entity) {
+                    let path = map.line_of_fire(origin, (x, y), weapon.range);
+                    for &(px, py) in &path {
+                        t.map
+                            .set_char_background(px, py, colors::DARK_YELLOW, BackgroundFlag::Set);
+                    }
+
+                    // the shot hits the first thing standing in its way
+                    let victim = path.iter().find(|&&(px, py)| {
+                        (&pos, &hp)
+                            .join()
+                            .any(|(p, hp)| hp.alive() && (p.x, p.y) == (px, py))
+                    });
+                    let penalty = heat.get(player_entity).map_or(0, Heat::accuracy_penalty)
+                        - Equipped::bonus(
+                            &equipped,
+                            &accuracy_bonus,
+                            &bodies,
+                            player_entity,
+                            |b| b.accuracy,
+                        );
+                    aim = Some(match victim {
+                        Some(&at) => {
+                            format!("Aim: {}%", weapon.hit_chance(distance(origin, at), penalty))
+                        }
+                        None => "Aim: --".to_string(),
+                    });
+                }
+                t.map
+                    .set_char_background(x, y, colors::RED, BackgroundFlag::Set);
+            }
+
+            // and what's under the cursor, as far as the player can tell
+            if let GameMode::Look { x, y } = *mode {
+                let seen: Vec<_> = (&entities, &pos)
+                    .join()
+                    .filter(|(_, p)| (p.x, p.y) == (x, y) && viewshed.can_see(x, y))
+                    .map(|(e, _)| Name::of(&names, e))
+                    .collect();
+                let tile = map.tile(x, y);
+                let what = if !seen.is_empty() {
+                    seen.join(", ")
+                } else if !tile.explored {
+                    "unexplored".to_string()
+                } else if tile.blocked {
+                    "a wall".to_string()
+                } else {
+                    "the floor".to_string()
+                };
+                aim = Some(format!("Look: {}", what));
+                t.map
+                    .set_char_background(x, y, colors::LIGHT_BLUE, BackgroundFlag::Set);
+            }
 
-            for (pos, mapr) in (&pos, &mapr).join() {
+            // non-blocking objects (corpses) first, so the living are drawn on top
+            let mut to_draw: Vec<_> = (&pos, &mapr, hp.maybe())
+                .join()
+                .filter(|(pos, _, _)| viewshed.can_see(pos.x, pos.y))
+                .collect();
+            to_draw.sort_by_key(|(_, _, hp)| hp.is_some());
+            for (pos, mapr, _) in to_draw {
+                t.map.set_default_foreground(mapr.color);
                 t.map.put_char(pos.x, pos.y, mapr.rep, BackgroundFlag::None);
             }
 
             blit(
-                &mut t.map,
+                &t.map,
                 (0, 0),
                 (SCREEN_WIDTH, SCREEN_HEIGHT),
                 &mut t.root,
@@ -44,13 +236,224 @@ impl<'a> System<'a> for TcodIntegration {
                 1.0,
             );
 
+            // the combat log covers the map while it's open
+            if let GameMode::CombatLog { scroll } = *mode {
+                render_combat_log(&mut t.root, &combat_log, scroll);
+            }
+
+            // and so does the backpack
+            if let GameMode::Inventory { selected, dropping } = *mode {
+                let carried: Vec<_> = (&entities, &items, &backpack)
+                    .join()
+                    .filter(|(_, _, b)| b.owner == player_entity)
+                    .map(|(e, item, _)| {
+                        let quantity = stacks.get(e).map_or(1, |s| s.quantity);
+                        (Name::of(&names, e), quantity, item.weight * quantity)
+                    })
+                    .collect();
+                let credits = wallets.get(player_entity).map_or(0, |w| w.credits);
+                render_inventory(&mut t.root, &carried, credits, selected, dropping);
+            }
+
+            if let GameMode::DropAmount { item, typed } = *mode {
+                let quantity = stacks.get(item).map_or(1, |s| s.quantity);
+                render_drop_amount(&mut t.root, &Name::of(&names, item), typed, quantity);
+            }
+
+            // and so does the crafting screen, greying out what can't be made yet
+            if let GameMode::Crafting = *mode {
+                let listed: Vec<_> = recipes
+                    .0
+                    .iter()
+                    .map(|recipe| {
+                        let ready =
+                            missing(recipe, &backpack, &stacks, &names, player_entity).is_none();
+                        (recipe.describe(), ready)
+                    })
+                    .collect();
+                render_crafting(&mut t.root, &listed);
+            }
+
+            // and so does the shop, showing one side of the deal at a time
+            if let GameMode::Shop { vendor, selling } = *mode {
+                let owner = if selling { player_entity } else { vendor };
+                let wares: Vec<_> = listed(&entities, &items, &backpack, owner)
+                    .into_iter()
+                    .map(|item| {
+                        let quantity = stacks.get(item).map_or(1, |s| s.quantity);
+                        let worth = price(&items, &stacks, item);
+                        let worth = if selling { offer(worth) } else { worth };
+                        (Name::of(&names, item), quantity, worth)
+                    })
+                    .collect();
+                let credits = |e| wallets.get(e).map_or(0, |w: &Wallet| w.credits);
+                render_shop(
+                    &mut t.root,
+                    &Name::of(&names, vendor),
+                    &wares,
+                    selling,
+                    (credits(player_entity), credits(vendor)),
+                );
+            }
+
+            // and so do the hardpoints
+            if let (GameMode::Loadout, Some(slots)) = (*mode, hardpoints.get(player_entity)) {
+                let mounted: Vec<_> = slots
+                    .slots
+                    .iter()
+                    .map(|&(slot, size)| {
+                        let item = Equipped::on(&equipped, &entities, player_entity, slot)
+                            .map(|item| Name::of(&names, item));
+                        (slot.name(), size.name(), item)
+                    })
+                    .collect();
+                let bonuses = [
+                    (
+                        "melee damage",
+                        Equipped::bonus(&equipped, &melee_bonus, &bodies, player_entity, |b| {
+                            b.attack
+                        }),
+                    ),
+                    (
+                        "defense",
+                        Equipped::bonus(&equipped, &defense_bonus, &bodies, player_entity, |b| {
+                            b.defense
+                        }),
+                    ),
+                    (
+                        "to hit",
+                        Equipped::bonus(&equipped, &accuracy_bonus, &bodies, player_entity, |b| {
+                            b.accuracy
+                        }),
+                    ),
+                    (
+                        "sprint heat",
+                        -Equipped::bonus(&equipped, &mobility_bonus, &bodies, player_entity, |b| {
+                            b.sprint_heat
+                        }),
+                    ),
+                ];
+                render_loadout(&mut t.root, &mounted, &bonuses);
+            }
+
+            // prepare to render the GUI panel
+            t.panel.set_default_background(colors::BLACK);
+            t.panel.clear();
+
+            if let Some(text) = aim {
+                t.panel.set_default_foreground(colors::LIGHT_GREY);
+                t.panel
+                    .print_ex(1, 0, BackgroundFlag::None, TextAlignment::Left, text);
+            }
+
+            // icons for the effects on the player, right above the bars
+            if let Some(effects) = effects.get(player_entity) {
+                let mut x = BAR_WIDTH;
+                for effect in effects.effects.iter().rev() {
+                    if effect.stacks > 1 {
+                        t.panel.set_default_foreground(colors::WHITE);
+                        t.panel.put_char(
+                            x,
+                            0,
+                            std::char::from_digit(effect.stacks as u32, 10).unwrap_or('+'),
+                            BackgroundFlag::None,
+                        );
+                        x -= 1;
+                    }
+                    let (icon, color) = effect.kind.icon();
+                    t.panel.set_default_foreground(color);
+                    t.panel.put_char(x, 0, icon, BackgroundFlag::None);
+                    x -= 2;
+                }
+            }
+
+            // show the player's stats
+            if let Some((hp, _)) = (&hp, &player).join().next() {
+                render_bar(
+                    &mut t.panel,
+                    1,
+                    1,
+                    BAR_WIDTH,
+                    "HP",
+                    hp.current,
+                    hp.max,
+                    colors::LIGHT_RED,
+                    colors::DARKER_RED,
+                );
+            }
+
+            if let Some((heat, _)) = (&heat, &player).join().next() {
+                let name = if heat.shut_down() { "SHUTDOWN" } else { "Heat" };
+                render_bar(
+                    &mut t.panel,
+                    1,
+                    2,
+                    BAR_WIDTH,
+                    name,
+                    heat.current,
+                    heat.max,
+                    colors::ORANGE,
+                    colors::DARKER_ORANGE,
+                );
+            }
+
+            // show the state of each part of the player's mech
+            if let Some((body, _)) = (&bodies, &player).join().next() {
+                render_parts(&mut t.panel, 1, 3, body);
+            }
+
+            // and the ammo left for the weapon in hand
+            if let Some(weapon) = weapons.get(player_entity) {
+                let text = match reloading.get(player_entity) {
+                    Some(r) => format!("{} reloading ({})", weapon.name, r.turns_left),
+                    None => format!(
+                        "{} {}/{} +{}",
+                        weapon.name,
+                        weapon.loaded,
+                        weapon.magazine,
+                        Ammo::carried(&ammo, &stacks, &backpack, player_entity, weapon.ammo_kind)
+                    ),
+                };
+                let color = if weapon.loaded > 0 {
+                    colors::LIGHT_GREY
+                } else {
+                    colors::LIGHT_RED
+                };
+                t.panel.set_default_foreground(color);
+                t.panel
+                    .print_ex(1, 6, BackgroundFlag::None, TextAlignment::Left, text);
+            }
+
+            // print the game messages, one line at a time
+            let mut y = MSG_HEIGHT as i32;
+            for msg in logs.messages.iter().rev() {
+                let msg_height = t.panel.get_height_rect(MSG_X, y, MSG_WIDTH, 0, msg);
+                y -= msg_height;
+                if y < 0 {
+                    break;
+                }
+                t.panel.set_default_foreground(colors::WHITE);
+                t.panel.print_rect(MSG_X, y, MSG_WIDTH, 0, msg);
+            }
+
+            // blit the contents of `panel` to the root console
+            blit(
+                &t.panel,
+                (0, 0),
+                (SCREEN_WIDTH, PANEL_HEIGHT),
+                &mut t.root,
+                (0, PANEL_Y),
+                1.0,
+                1.0,
+            );
+
             t.root.flush();
 
             // cleanup
             for (pos, _) in (&pos, &mapr).join() {
                 t.map.put_char(pos.x, pos.y, ' ', BackgroundFlag::None);
             }
-        });
+        }
     }
 
     fn setup(&mut self, res: &mut Resources) {
@@ -66,15 +469,479 @@ impl<'a> System<'a> for TcodIntegration {
 
         let map = Offscreen::new(MAP_WIDTH, MAP_HEIGHT);
         let panel = Offscreen::new(SCREEN_WIDTH, PANEL_HEIGHT);
-        let fov = FovMap::new(MAP_WIDTH, MAP_HEIGHT);
 
-        self.tcod = Some(Tcod {
-            root,
-            map,
-            fov,
-            panel,
-        });
+        self.tcod = Some(Tcod { root, map, panel });
 
         tcod::system::set_fps(LIMIT_FPS);
     }
 }
+
+#[allow(clippy::too_many_arguments)]
+fn render_bar(
+    panel: &mut Offscreen,
+    x: i32,
+    y: i32,
+    total_width: i32,
+    name: &str,
+    value: i32,
+    maximum: i32,
+    bar_color: Color,
+    back_color: Color,
+) {
+    // render a bar (HP, experience, etc). First calculate the width of the bar
+    let bar_width = (value as f32 / maximum as f32 * total_width as f32) as i32;
+    // past the maximum still only fills the bar
+    let bar_width = bar_width.min(total_width);
+
+    // render the background first
+    panel.set_default_background(back_color);
+    panel.rect(x, y, total_width, 1, false, BackgroundFlag::Screen);
+
+    // now render the bar on top
+    panel.set_default_background(bar_color);
+    if bar_width > 0 {
+        panel.rect(x, y, bar_width, 1, false, BackgroundFlag::Screen);
+    }
+
+    // finally, some centered text with the values
+    panel.set_default_foreground(colors::WHITE);
+    panel.print_ex(
+        x + total_width / 2,
+        y,
+        BackgroundFlag::None,
+        TextAlignment::Center,
+        format!("{}: {}/{}", name, value, maximum),
+    );
+}
+
+fn render_parts(panel: &mut Offscreen, x: i32, y: i32, body: &MechBody) {
+    use PartKind::*;
+
+    // two parts per line, to fit under the bars
+    let rows = [
+        [Some(Torso), Some(Sensors)],
+        [Some(LeftArm), Some(RightArm)],
+        [Some(Legs), None],
+    ];
+
+    for (dy, row) in rows.iter().enumerate() {
+        for (dx, kind) in row.iter().enumerate() {
+            let part = match kind.and_then(|k| body.part(k)) {
+                Some(part) => part,
+                None => continue,
+            };
+
+            let ratio = part.structure as f32 / part.max_structure as f32;
+            let color = if part.destroyed() {
+                colors::DARK_GREY
+            } else if ratio > 0.66 {
+                colors::LIGHT_GREEN
+            } else if ratio > 0.33 {
+                colors::YELLOW
+            } else {
+                colors::LIGHT_RED
+            };
+
+            // armor first, then what's left of the structure behind it
+            let x = x + dx as i32 * (BAR_WIDTH / 2);
+            let y = y + dy as i32;
+            let armor = if part.destroyed() {
+                "-".to_string()
+            } else {
+                part.armor.to_string()
+            };
+            let structure = if part.destroyed() {
+                "+--".to_string()
+            } else {
+                format!("+{}", part.structure)
+            };
+
+            panel.set_default_foreground(color);
+            panel.print_ex(
+                x,
+                y,
+                BackgroundFlag::None,
+                TextAlignment::Left,
+                part.kind.abbr(),
+            );
+            panel.set_default_foreground(if part.armor > 0 {
+                colors::SKY
+            } else {
+                colors::DARK_GREY
+            });
+            panel.print_ex(x + 3, y, BackgroundFlag::None, TextAlignment::Left, &armor);
+            panel.set_default_foreground(color);
+            panel.print_ex(
+                x + 3 + armor.len() as i32,
+                y,
+                BackgroundFlag::None,
+                TextAlignment::Left,
+                structure,
+            );
+        }
+    }
+}
+
+fn render_combat_log(root: &mut Root, combat_log: &CombatLog, scroll: usize) {
+    let width = MAP_WIDTH - 4;
+    let height = MAP_HEIGHT - 4;
+    let mut window = Offscreen::new(width, height);
+
+    window.set_default_foreground(colors::WHITE);
+    window.print_ex(
+        0,
+        0,
+        BackgroundFlag::None,
+        TextAlignment::Left,
+        "Combat log - arrows scroll, Esc closes",
+    );
+
+    if !combat_log.enabled {
+        window.set_default_foreground(colors::LIGHT_GREY);
+        window.print_rect(
+            0,
+            2,
+            width,
+            0,
+            "Nothing recorded, start the game with --combat-log to keep track of attacks.",
+        );
+    }
+
+    // newest records at the bottom, scrolling goes back in time
+    let shown = combat_log.records.len().saturating_sub(scroll);
+    let mut y = height;
+    for (i, record) in combat_log.records[..shown].iter().enumerate().rev() {
+        let line = format!("{:5} {}", i + 1, record.describe());
+        y -= window.get_height_rect(0, y, width, 0, &line);
+        if y < 2 {
+            break;
+        }
+        let color = if record.outcome.is_some() {
+            colors::LIGHT_GREY
+        } else {
+            colors::GREY
+        };
+        window.set_default_foreground(color);
+        window.print_rect(0, y, width, 0, &line);
+    }
+
+    blit(&window, (0, 0), (width, height), root, (2, 2), 1.0, 0.85);
+}
+
+fn render_inventory(
+    root: &mut Root,
+    carried: &[(String, i32, i32)],
+    credits: i32,
+    selected: Option<usize>,
+    dropping: bool,
+) {
+    let width = 40;
+    let height = carried.len() as i32 + 5;
+    let mut window = Offscreen::new(width, height);
+
+    let total: i32 = carried.iter().map(|(_, _, weight)| weight).sum();
+    let title = if dropping {
+        "Drop which item?".to_string()
+    } else {
+        format!(
+            "Backpack {}/{}, weight {}, {} credits",
+            carried.len(),
+            BACKPACK_CAPACITY,
+            total,
+            credits
+        )
+    };
+    window.set_default_foreground(colors::WHITE);
+    window.print_ex(0, 0, BackgroundFlag::None, TextAlignment::Left, title);
+
+    if carried.is_empty() {
+        window.set_default_foreground(colors::LIGHT_GREY);
+        window.print_ex(0, 2, BackgroundFlag::None, TextAlignment::Left, "Nothing.");
+    }
+
+    for (i, (name, quantity, weight)) in carried.iter().enumerate() {
+        let y = i as i32 + 2;
+        let letter = (b'a' + i as u8) as char;
+        let text = if *quantity > 1 {
+            format!("{}) {} x{}", letter, name, quantity)
+        } else {
+            format!("{}) {}", letter, name)
+        };
+
+        let color = if selected == Some(i) {
+            colors::YELLOW
+        } else {
+            colors::LIGHT_GREY
+        };
+        window.set_default_foreground(color);
+        window.print_ex(0, y, BackgroundFlag::None, TextAlignment::Left, text);
+        window.print_ex(
+            width - 1,
+            y,
+            BackgroundFlag::None,
+            TextAlignment::Right,
+            format!("{}kg", weight),
+        );
+    }
+
+    let help = if selected.is_some() {
+        "u)se d)rop e)quip i)nspect, Esc back"
+    } else {
+        "pick an item, Esc closes"
+    };
+    window.set_default_foreground(colors::GREY);
+    window.print_ex(
+        0,
+        height - 1,
+        BackgroundFlag::None,
+        TextAlignment::Left,
+        help,
+    );
+
+    let x = (MAP_WIDTH - width) / 2;
+    let y = (MAP_HEIGHT - height) / 2;
+    blit(&window, (0, 0), (width, height), root, (x, y), 1.0, 0.85);
+}
+
+fn render_drop_amount(root: &mut Root, name: &str, typed: i32, quantity: i32) {
+    let width = 40;
+    let height = 4;
+    let mut window = Offscreen::new(width, height);
+
+    window.set_default_foreground(colors::WHITE);
+    window.print_ex(
+        0,
+        0,
+        BackgroundFlag::None,
+        TextAlignment::Left,
+        format!("Drop how many {}?", name),
+    );
+
+    let amount = if typed > 0 {
+        typed.to_string()
+    } else {
+        "all".to_string()
+    };
+    window.set_default_foreground(colors::YELLOW);
+    window.print_ex(
+        0,
+        1,
+        BackgroundFlag::None,
+        TextAlignment::Left,
+        format!("{} of {}", amount, quantity),
+    );
+
+    window.set_default_foreground(colors::GREY);
+    window.print_ex(
+        0,
+        height - 1,
+        BackgroundFlag::None,
+        TextAlignment::Left,
+        "type a number, Enter drops, Esc back",
+    );
+
+    let x = (MAP_WIDTH - width) / 2;
+    let y = (MAP_HEIGHT - height) / 2;
+    blit(&window, (0, 0), (width, height), root, (x, y), 1.0, 0.85);
+}
+
+fn render_crafting(root: &mut Root, recipes: &[(String, bool)]) {
+    let width = 50;
+    let height = recipes.len() as i32 + 4;
+    let mut window = Offscreen::new(width, height);
+
+    window.set_default_foreground(colors::WHITE);
+    window.print_ex(0, 0, BackgroundFlag::None, TextAlignment::Left, "Crafting");
+
+    if recipes.is_empty() {
+        window.set_default_foreground(colors::LIGHT_GREY);
+        window.print_ex(
+            0,
+            2,
+            BackgroundFlag::None,
+            TextAlignment::Left,
+            "No recipes known.",
+        );
+    }
+
+    for (i, (recipe, ready)) in recipes.iter().enumerate() {
+        let letter = (b'a' + i as u8) as char;
+        let color = if *ready {
+            colors::LIGHT_GREY
+        } else {
+            colors::DARK_GREY
+        };
+        window.set_default_foreground(color);
+        window.print_ex(
+            0,
+            i as i32 + 2,
+            BackgroundFlag::None,
+            TextAlignment::Left,
+            format!("{}) {}", letter, recipe),
+        );
+    }
+
+    window.set_default_foreground(colors::GREY);
+    window.print_ex(
+        0,
+        height - 1,
+        BackgroundFlag::None,
+        TextAlignment::Left,
+        "pick a recipe, Esc closes",
+    );
+
+    let x = (MAP_WIDTH - width) / 2;
+    let y = (MAP_HEIGHT - height) / 2;
+    blit(&window, (0, 0), (width, height), root, (x, y), 1.0, 0.85);
+}
+
+fn render_shop(
+    root: &mut Root,
+    vendor: &str,
+    wares: &[(String, i32, i32)],
+    selling: bool,
+    (credits, vendor_credits): (i32, i32),
+) {
+    let width = 50;
+    let height = wares.len() as i32 + 5;
+    let mut window = Offscreen::new(width, height);
+
+    let title = if selling {
+        format!("Selling to the {} ({} credits)", vendor, vendor_credits)
+    } else {
+        format!("Buying from the {}", vendor)
+    };
+    window.set_default_foreground(colors::WHITE);
+    window.print_ex(0, 0, BackgroundFlag::None, TextAlignment::Left, title);
+    window.print_ex(
+        width - 1,
+        0,
+        BackgroundFlag::None,
+        TextAlignment::Right,
+        format!("you: {}", credits),
+    );
+
+    if wares.is_empty() {
+        window.set_default_foreground(colors::LIGHT_GREY);
+        window.print_ex(0, 2, BackgroundFlag::None, TextAlignment::Left, "Nothing.");
+    }
+
+    for (i, (name, quantity, worth)) in wares.iter().enumerate() {
+        let y = i as i32 + 2;
+        let letter = (b'a' + i as u8) as char;
+        let text = if *quantity > 1 {
+            format!("{}) {} x{}", letter, name, quantity)
+        } else {
+            format!("{}) {}", letter, name)
+        };
+
+        // greyed out when whoever pays can't
+        let affordable = if selling {
+            *worth <= vendor_credits
+        } else {
+            *worth <= credits
+        };
+        let color = if affordable {
+            colors::LIGHT_GREY
+        } else {
+            colors::DARK_GREY
+        };
+        window.set_default_foreground(color);
+        window.print_ex(0, y, BackgroundFlag::None, TextAlignment::Left, text);
+        window.print_ex(
+            width - 1,
+            y,
+            BackgroundFlag::None,
+            TextAlignment::Right,
+            format!("{}cr", worth),
+        );
+    }
+
+    let help = if selling {
+        "pick an item to sell, Tab to buy, Esc closes"
+    } else {
+        "pick an item to buy, Tab to sell, Esc closes"
+    };
+    window.set_default_foreground(colors::GREY);
+    window.print_ex(
+        0,
+        height - 1,
+        BackgroundFlag::None,
+        TextAlignment::Left,
+        help,
+    );
+
+    let x = (MAP_WIDTH - width) / 2;
+    let y = (MAP_HEIGHT - height) / 2;
+    blit(&window, (0, 0), (width, height), root, (x, y), 1.0, 0.85);
+}
+
+fn render_loadout(
+    root: &mut Root,
+    mounted: &[(&str, &str, Option<String>)],
+    bonuses: &[(&str, i32)],
+) {
+    let width = 40;
+    let height = mounted.len() as i32 + bonuses.len() as i32 + 6;
+    let mut window = Offscreen::new(width, height);
+
+    window.set_default_foreground(colors::WHITE);
+    window.print_ex(0, 0, BackgroundFlag::None, TextAlignment::Left, "Loadout");
+
+    for (i, (slot, size, item)) in mounted.iter().enumerate() {
+        let y = i as i32 + 2;
+        let letter = (b'a' + i as u8) as char;
+        let (text, color) = match item {
+            Some(item) => (item.as_str(), colors::LIGHT_GREY),
+            None => ("-", colors::DARK_GREY),
+        };
+
+        window.set_default_foreground(colors::GREY);
+        window.print_ex(
+            0,
+            y,
+            BackgroundFlag::None,
+            TextAlignment::Left,
+            format!("{}) {} {}", letter, size, slot),
+        );
+        window.set_default_foreground(color);
+        window.print_ex(
+            width - 1,
+            y,
+            BackgroundFlag::None,
+            TextAlignment::Right,
+            text,
+        );
+    }
+
+    // what all of it adds up to
+    let top = mounted.len() as i32 + 3;
+    for (i, (name, value)) in bonuses.iter().enumerate() {
+        let color = if *value != 0 {
+            colors::LIGHT_GREEN
+        } else {
+            colors::DARK_GREY
+        };
+        window.set_default_foreground(color);
+        window.print_ex(
+            0,
+            top + i as i32,
+            BackgroundFlag::None,
+            TextAlignment::Left,
+            format!("{:+} {}", value, name),
+        );
+    }
+
+    window.set_default_foreground(colors::GREY);
+    window.print_ex(
+        0,
+        height - 1,
+        BackgroundFlag::None,
+        TextAlignment::Left,
+        "pick a hardpoint to take its item off, Esc closes",
+    );
+
+    let x = (MAP_WIDTH - width) / 2;
+    let y = (MAP_HEIGHT - height) / 2;
+    blit(&window, (0, 0), (width, height), root, (x, y), 1.0, 0.85);
+}
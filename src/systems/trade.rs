@@ -0,0 +1,101 @@
+use specs::{join::Join, Entities, Entity, ReadStorage, System, Write, WriteStorage};
+
+use super::shop::{offer, price};
+use crate::components::{InBackpack, Item, Name, Stackable, Wallet, WantsToBuy, WantsToSell};
+use crate::resources::MesLogs;
+
+#[derive(Default)]
+pub struct Trade;
+
+impl<'a> System<'a> for Trade {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, WantsToBuy>,
+        WriteStorage<'a, WantsToSell>,
+        WriteStorage<'a, Wallet>,
+        WriteStorage<'a, InBackpack>,
+        WriteStorage<'a, Stackable>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, Name>,
+        Write<'a, MesLogs>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut wants_buy,
+            mut wants_sell,
+            mut wallets,
+            mut backpack,
+            mut stacks,
+            items,
+            names,
+            mut logs,
+        ) = data;
+
+        // (buyer, seller, item, credits changing hands)
+        let mut deals: Vec<(Entity, Entity, Entity, i32)> = vec![];
+        for (e, wants) in (&entities, &wants_buy).join() {
+            deals.push((
+                e,
+                wants.vendor,
+                wants.item,
+                price(&items, &stacks, wants.item),
+            ));
+        }
+        for (e, wants) in (&entities, &wants_sell).join() {
+            let offer = offer(price(&items, &stacks, wants.item));
+            deals.push((wants.vendor, e, wants.item, offer));
+        }
+        wants_buy.clear();
+        wants_sell.clear();
+
+        for (buyer, seller, item, credits) in deals {
+            if backpack.get(item).is_none_or(|b| b.owner != seller) {
+                continue;
+            }
+            match wallets.get_mut(buyer) {
+                Some(wallet) if wallet.credits >= credits => wallet.credits -= credits,
+                _ => {
+                    logs.add_message(format!(
+                        "{} can't afford the {} ({} credits).",
+                        Name::of(&names, buyer),
+                        Name::of(&names, item),
+                        credits
+                    ));
+                    continue;
+                }
+            }
+            if let Some(wallet) = wallets.get_mut(seller) {
+                wallet.credits += credits;
+            }
+
+            logs.add_message(format!(
+                "{} buys the {} from {} for {} credits.",
+                Name::of(&names, buyer),
+                Name::of(&names, item),
+                Name::of(&names, seller),
+                credits
+            ));
+
+            // onto the buyer's stacks first, whatever is left is carried as is
+            let mut left = stacks.get(item).map_or(0, |s| s.quantity);
+            for stack in Stackable::matching(&stacks, &backpack, &names, &entities, buyer, item) {
+                if let Some(stack) = stacks.get_mut(stack) {
+                    let moved = (stack.limit - stack.quantity).min(left);
+                    stack.quantity += moved;
+                    left -= moved;
+                }
+            }
+            if let Some(bought) = stacks.get_mut(item) {
+                bought.quantity = left;
+            }
+
+            if stacks.contains(item) && left <= 0 {
+                let _ = entities.delete(item);
+            } else {
+                let _ = backpack.insert(item, InBackpack { owner: buyer });
+            }
+        }
+    }
+}
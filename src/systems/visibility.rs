@@ -0,0 +1,73 @@
+use specs::{join::Join, Entities, Read, ReadStorage, System, WriteExpect, WriteStorage};
+
+use crate::components::{EffectKind, Flare, MechBody, Player, Position, StatusEffects, Viewshed};
+use crate::map::Map;
+use crate::resources::PlayerTurn;
+
+#[derive(Default)]
+pub struct Visibility;
+
+impl<'a> System<'a> for Visibility {
+    type SystemData = (
+        Entities<'a>,
+        WriteExpect<'a, Map>,
+        ReadStorage<'a, Position>,
+        WriteStorage<'a, Viewshed>,
+        ReadStorage<'a, MechBody>,
+        ReadStorage<'a, StatusEffects>,
+        ReadStorage<'a, Player>,
+        WriteStorage<'a, Flare>,
+        Read<'a, PlayerTurn>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut map, pos, mut viewsheds, bodies, effects, player, mut flares, turn) =
+            data;
+
+        // flares burn down with every turn
+        if turn.0 {
+            for (e, flare) in (&entities, &mut flares).join() {
+                flare.turns_left -= 1;
+                if flare.turns_left <= 0 {
+                    let _ = entities.delete(e);
+                }
+            }
+        }
+
+        for (pos, viewshed, body, effects, player) in (
+            &pos,
+            &mut viewsheds,
+            bodies.maybe(),
+            effects.maybe(),
+            player.maybe(),
+        )
+            .join()
+        {
+            // monsters only need to look around when they have a turn to take
+            if player.is_none() && !turn.0 {
+                continue;
+            }
+
+            // wrecked sensors don't see as far, neither do scrambled ones
+            let mut radius = body.map_or(viewshed.range, |b| b.sight_radius(viewshed.range));
+            if effects.is_some_and(|s| s.has(EffectKind::Emp)) {
+                radius /= 2;
+            }
+            viewshed.visible = map
+                .field_of_view((pos.x, pos.y), radius)
+                .into_iter()
+                .collect();
+
+            if player.is_some() {
+                // whatever a flare lights up is in plain sight
+                for flare in (&flares).join().filter(|f| f.turns_left > 0) {
+                    viewshed.visible.extend(flare.tiles.iter().cloned());
+                }
+
+                for &(x, y) in &viewshed.visible {
+                    map.tile_mut(x, y).explored = true;
+                }
+            }
+        }
+    }
+}